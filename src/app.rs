@@ -1,8 +1,29 @@
-use crate::data::{MyeonData, Priority, Task, TaskStatus};
+use crate::boards::{self, BoardEntry, BoardRegistry};
+use crate::data::{
+    self, BoardConfig, DEFAULT_CONTEXT, MyeonData, Priority, SortMode, Task, TaskEdit, TaskStatus,
+    TrashedTask, Urgency,
+};
+use crate::error::AppError;
+use crate::history::{self, History};
+use crate::query::Query;
+use chrono::Utc;
+use std::path::PathBuf;
+use std::time::SystemTime;
 
 pub enum InputMode {
     Normal,
     Editing,
+    Recovery,
+    BoardPicker,
+    Confirm,
+    Trash,
+    Archive,
+    Detail,
+    Stats,
+    Flow,
+    Matrix,
+    Search,
+    ViewPicker,
 }
 
 pub enum EditField {
@@ -10,12 +31,74 @@ pub enum EditField {
     Description,
     Context,
     Priority,
+    Urgency,
+}
+
+/// Set when the board could not be loaded. While present, nothing is
+/// written to disk until the user recovers the file or starts fresh.
+pub struct Recovery {
+    pub reason: String,
+    pub quarantined: Option<PathBuf>,
+    /// False when the file is from a newer myeon: it is fine, so it must not be replaced.
+    pub can_start_fresh: bool,
+}
+
+/// An action that waits for a yes/no answer before it runs.
+pub enum PendingAction {
+    MoveTask { id: uuid::Uuid, target: usize },
+    DeleteTask { id: uuid::Uuid },
+    PurgeTask { id: uuid::Uuid },
+}
+
+pub struct Confirm {
+    pub prompt: String,
+    pub action: PendingAction,
+}
+
+/// Names of the Eisenhower quadrants, indexed like `App::matrix_tasks`.
+pub const MATRIX_QUADRANTS: [&str; 4] = ["Do", "Schedule", "Delegate", "Drop"];
+
+/// Quadrant a task belongs in, see `App::matrix_tasks`.
+fn matrix_quadrant(task: &Task) -> usize {
+    let important = task.priority != Priority::Low;
+    let urgent = task.urgency != Urgency::Low;
+    match (important, urgent) {
+        (true, true) => 0,
+        (true, false) => 1,
+        (false, true) => 2,
+        (false, false) => 3,
+    }
 }
 
 pub struct App {
+    pub board_path: PathBuf,
+    pub board_name: String,
+    pub board_list: Vec<BoardEntry>,
+    pub board_list_index: usize,
     pub column_index: usize,
     pub selected_task_index: usize,
+    /// Index of the first visible card in each column, kept up to date by the renderer.
+    pub column_scroll: Vec<usize>,
+    pub config: BoardConfig,
     pub all_tasks: Vec<Task>,
+    pub trash: Vec<TrashedTask>,
+    pub trash_index: usize,
+    /// Task shown in the detail view.
+    pub detail_task_id: Option<uuid::Uuid>,
+    /// Focused quadrant of the Eisenhower matrix, see `matrix_tasks`.
+    pub matrix_quadrant: usize,
+    pub matrix_index: usize,
+    pub archive_index: usize,
+    /// Text the archive browser is filtered by.
+    pub archive_query: String,
+    /// True while keys go into `archive_query` instead of moving the selection.
+    pub archive_searching: bool,
+    /// Text the board is filtered by, see `Task::matches_fuzzy`. Empty shows every card.
+    pub search_query: String,
+    /// Saved view the board is filtered by, with its parsed query.
+    pub active_view: Option<(String, Query)>,
+    /// Highlighted row of the view picker; 0 is "all tasks".
+    pub view_index: usize,
     pub current_context: String,
     pub input: String,
     pub input_mode: InputMode,
@@ -23,32 +106,56 @@ pub struct App {
     pub editing_task_id: Option<uuid::Uuid>,
     pub active_edit_field: EditField,
     pub editing_priority: Priority,
+    pub editing_urgency: Urgency,
     pub editing_context: String,
     pub editing_description: String,
     pub context_list_index: usize,
+    pub recovery: Option<Recovery>,
+    pub confirm: Option<Confirm>,
+    pub status_message: Option<String>,
+    /// The board as last read from or written to disk, used as the merge base
+    /// when another instance saved in the meantime.
+    synced: MyeonData,
+    synced_modified: Option<SystemTime>,
+    history: History,
 }
 
 impl App {
-    pub fn new() -> App {
-        let data = MyeonData::load();
-        let tasks = if data.tasks.is_empty() {
-            vec![Task {
-                id: uuid::Uuid::new_v4(),
-                title: "Welcome to myeon. Press 'a' to add a task.".to_string(),
-                description: None,
-                status: TaskStatus::Idea,
-                priority: Priority::Low,
-                context: "General".to_string(),
-                created_at: chrono::Utc::now(),
-            }]
-        } else {
-            data.tasks
-        };
+    pub fn new(board_path: PathBuf) -> App {
+        match MyeonData::load(&board_path) {
+            Ok(data) => App::from_data(data, board_path),
+            Err(e) => {
+                let mut app = App::from_data(MyeonData::default(), board_path);
+                app.enter_recovery(e);
+                app
+            }
+        }
+    }
 
+    fn from_data(data: MyeonData, board_path: PathBuf) -> App {
         App {
+            synced_modified: MyeonData::modified(&board_path),
+            history: History::load(&board_path),
+            board_name: boards::display_name(&board_path),
+            board_path,
+            board_list: Vec::new(),
+            board_list_index: 0,
             column_index: 0,
             selected_task_index: 0,
-            all_tasks: tasks,
+            column_scroll: Vec::new(),
+            config: data.config.clone(),
+            all_tasks: Self::tasks_or_welcome(data.tasks.clone(), &data.config),
+            trash: data.trash.clone(),
+            trash_index: 0,
+            detail_task_id: None,
+            matrix_quadrant: 0,
+            matrix_index: 0,
+            archive_index: 0,
+            archive_query: String::new(),
+            archive_searching: false,
+            search_query: String::new(),
+            active_view: None,
+            view_index: 0,
             current_context: "All".to_string(),
             input: String::new(),
             input_mode: InputMode::Normal,
@@ -56,9 +163,120 @@ impl App {
             editing_task_id: None,
             active_edit_field: EditField::Title,
             editing_priority: Priority::Low,
+            editing_urgency: Urgency::Low,
             editing_context: String::new(),
             editing_description: String::new(),
             context_list_index: 0,
+            recovery: None,
+            confirm: None,
+            status_message: None,
+            synced: data,
+        }
+    }
+
+    fn tasks_or_welcome(tasks: Vec<Task>, config: &BoardConfig) -> Vec<Task> {
+        if !tasks.is_empty() {
+            return tasks;
+        }
+        vec![Task::new(
+            "Welcome to myeon. Press 'a' to add a task.",
+            config.first_status(),
+            Priority::Low,
+            None,
+            None,
+        )]
+    }
+
+    fn enter_recovery(&mut self, error: AppError) {
+        let quarantined = match &error {
+            AppError::CorruptData { path, .. } | AppError::Quarantined { path } => {
+                Some(path.clone())
+            }
+            _ => None,
+        };
+        self.recovery = Some(Recovery {
+            can_start_fresh: !matches!(error, AppError::UnsupportedSchema { .. }),
+            reason: error.to_string(),
+            quarantined,
+        });
+        self.input_mode = InputMode::Recovery;
+    }
+
+    /// Tries to load the board again: the quarantined file if there is one
+    /// (so a hand-fixed file can be put back), otherwise the data path.
+    pub fn recover(&mut self) {
+        let Some(recovery) = &self.recovery else {
+            return;
+        };
+        let result = match &recovery.quarantined {
+            Some(path) => MyeonData::restore_quarantined(path, &self.board_path),
+            None => MyeonData::load(&self.board_path),
+        };
+        match result {
+            Ok(data) => {
+                *self = App::from_data(data, self.board_path.clone());
+            }
+            Err(e) => {
+                if let Some(recovery) = self.recovery.as_mut() {
+                    recovery.reason = e.to_string();
+                }
+            }
+        }
+    }
+
+    /// Abandons the unreadable board and starts with an empty one.
+    /// The quarantined file is left on disk untouched; the new board file
+    /// tells later loads that it was given up on.
+    pub fn start_fresh(&mut self) {
+        if !self.recovery.as_ref().is_some_and(|r| r.can_start_fresh) {
+            return;
+        }
+        if let Err(e) = MyeonData::default().save(&self.board_path) {
+            if let Some(recovery) = self.recovery.as_mut() {
+                recovery.reason = e.to_string();
+            }
+            return;
+        }
+        *self = App::new(self.board_path.clone());
+        self.persist();
+    }
+
+    /// Opens the board picker with every known board, plus the current one
+    /// if it is a project board outside the registry.
+    pub fn open_board_picker(&mut self) {
+        let mut list = BoardRegistry::open()
+            .map(|registry| registry.list())
+            .unwrap_or_default();
+        if !list.iter().any(|b| b.path == self.board_path) {
+            list.insert(
+                0,
+                BoardEntry {
+                    name: self.board_name.clone(),
+                    path: self.board_path.clone(),
+                },
+            );
+        }
+        self.board_list_index = list
+            .iter()
+            .position(|b| b.path == self.board_path)
+            .unwrap_or(0);
+        self.board_list = list;
+        self.input_mode = InputMode::BoardPicker;
+    }
+
+    /// Loads the board highlighted in the picker in place of the current one.
+    pub fn switch_board(&mut self) {
+        let Some(entry) = self.board_list.get(self.board_list_index).cloned() else {
+            self.input_mode = InputMode::Normal;
+            return;
+        };
+        if entry.path == self.board_path {
+            self.input_mode = InputMode::Normal;
+            return;
+        }
+        *self = App::new(entry.path);
+        if self.recovery.is_none() {
+            self.status_message = Some(format!("Opened board '{}'", self.board_name));
         }
     }
 
@@ -69,11 +287,16 @@ impl App {
     }
 
     pub fn get_task_contexts(&self) -> Vec<String> {
-        let mut contexts: Vec<String> = self.all_tasks.iter().map(|t| t.context.clone()).collect();
+        let mut contexts: Vec<String> = self
+            .all_tasks
+            .iter()
+            .filter(|t| !t.is_archived())
+            .map(|t| t.context.clone())
+            .collect();
         contexts.sort();
         contexts.dedup();
-        if !contexts.contains(&"General".to_string()) {
-            contexts.insert(0, "General".to_string());
+        if !contexts.iter().any(|c| c == DEFAULT_CONTEXT) {
+            contexts.insert(0, DEFAULT_CONTEXT.to_string());
         }
         contexts
     }
@@ -94,56 +317,196 @@ impl App {
             return;
         }
 
+        let before = self.all_tasks.clone();
+        let label = if self.is_editing_existing {
+            format!("edit '{}'", self.input)
+        } else {
+            format!("add '{}'", self.input)
+        };
         if self.is_editing_existing {
-            if let Some(id) = self.editing_task_id {
-                if let Some(task) = self.all_tasks.iter_mut().find(|t| t.id == id) {
-                    task.title = self.input.clone();
-                    task.description = if self.editing_description.is_empty() {
-                        task.description.clone()
-                    } else {
-                        Some(self.editing_description.clone())
-                    };
-                    task.context = if self.editing_context.is_empty() {
-                        task.context.clone()
-                    } else {
-                        self.editing_context.clone()
-                    };
-                    task.priority = self.editing_priority.clone();
-                }
+            if let Some(id) = self.editing_task_id
+                && let Some(task) = self.all_tasks.iter_mut().find(|t| t.id == id)
+            {
+                task.apply(TaskEdit {
+                    title: Some(self.input.clone()),
+                    description: Some(self.editing_description.clone()),
+                    context: Some(self.editing_context.clone()),
+                    priority: Some(self.editing_priority.clone()),
+                    urgency: Some(self.editing_urgency.clone()),
+                });
             }
             self.is_editing_existing = false;
             self.editing_task_id = None;
         } else {
-            let new_task = Task {
-                id: uuid::Uuid::new_v4(),
-                title: self.input.clone(),
-                description: None,
-                status: TaskStatus::Idea,
-                priority: self.editing_priority.clone(),
-                context: if self.editing_context.is_empty() {
-                    "General".to_string()
-                } else {
-                    self.editing_context.clone()
-                },
-                created_at: chrono::Utc::now(),
-            };
-            self.all_tasks.push(new_task);
+            let mut task = Task::new(
+                self.input.clone(),
+                self.config.first_status(),
+                self.editing_priority.clone(),
+                Some(self.editing_context.clone()),
+                Some(self.editing_description.clone()),
+            );
+            task.urgency = self.editing_urgency.clone();
+            task.rank = data::bottom_rank(&self.all_tasks, &task.status);
+            self.all_tasks.push(task);
         }
 
         self.reset_editing_state();
-        self.persist();
+        self.commit(label, before);
     }
 
+    /// Asks before moving the selected task to the trash.
     pub fn delete_task(&mut self) {
         let current_tasks = self.get_current_column_tasks();
-        if let Some(task_to_delete) = current_tasks.get(self.selected_task_index) {
-            let id = task_to_delete.id;
-            self.all_tasks.retain(|t| t.id != id);
-            if self.selected_task_index > 0 {
-                self.selected_task_index -= 1;
-            }
+        let Some(task) = current_tasks.get(self.selected_task_index) else {
+            return;
+        };
+        self.confirm = Some(Confirm {
+            prompt: format!("Move '{}' to the trash?", task.title),
+            action: PendingAction::DeleteTask { id: task.id },
+        });
+        self.input_mode = InputMode::Confirm;
+    }
+
+    fn trash_task(&mut self, id: uuid::Uuid) {
+        let Some(task) = self.all_tasks.iter().find(|t| t.id == id) else {
+            return;
+        };
+        let label = format!("delete '{}'", task.title);
+        let before = self.all_tasks.clone();
+        self.all_tasks.retain(|t| t.id != id);
+        self.clamp_selection();
+        self.commit(label, before);
+    }
+
+    /// The context to limit views like stats to, `None` for all of them.
+    pub fn context_filter(&self) -> Option<&str> {
+        (self.current_context != "All").then_some(self.current_context.as_str())
+    }
+
+    pub fn open_detail(&mut self) {
+        let current_tasks = self.get_current_column_tasks();
+        if let Some(task) = current_tasks.get(self.selected_task_index) {
+            self.detail_task_id = Some(task.id);
+            self.input_mode = InputMode::Detail;
         }
-        self.persist();
+    }
+
+    pub fn detail_task(&self) -> Option<&Task> {
+        let id = self.detail_task_id?;
+        self.all_tasks.iter().find(|t| t.id == id)
+    }
+
+    /// Display name of the column `status` refers to, or the raw id if it is gone.
+    pub fn column_name<'a>(&'a self, status: &'a TaskStatus) -> &'a str {
+        self.config
+            .column_index(status)
+            .map_or(&status.0, |i| &self.config.columns[i].name)
+    }
+
+    /// Takes the selected task off the board into the archive.
+    pub fn archive_selected(&mut self) {
+        let current_tasks = self.get_current_column_tasks();
+        let Some(id) = current_tasks.get(self.selected_task_index).map(|t| t.id) else {
+            return;
+        };
+        let before = self.all_tasks.clone();
+        let Some(task) = self.all_tasks.iter_mut().find(|t| t.id == id) else {
+            return;
+        };
+        let label = format!("archive '{}'", task.title);
+        task.archive();
+        self.clamp_selection();
+        self.commit(label, before);
+    }
+
+    pub fn open_archive(&mut self) {
+        self.archive_index = 0;
+        self.archive_query.clear();
+        self.archive_searching = false;
+        self.input_mode = InputMode::Archive;
+    }
+
+    /// Archived tasks matching the archive search, most recently archived first.
+    pub fn archived_tasks(&self) -> Vec<&Task> {
+        let mut tasks: Vec<&Task> = self
+            .all_tasks
+            .iter()
+            .filter(|t| t.is_archived())
+            .filter(|t| self.archive_query.is_empty() || t.matches_text(&self.archive_query))
+            .collect();
+        tasks.sort_by_key(|t| std::cmp::Reverse(t.archived_at));
+        tasks
+    }
+
+    /// Puts the highlighted archived task back on the board.
+    pub fn unarchive_selected(&mut self) {
+        let Some(id) = self.archived_tasks().get(self.archive_index).map(|t| t.id) else {
+            return;
+        };
+        let before = self.all_tasks.clone();
+        let Some(task) = self.all_tasks.iter_mut().find(|t| t.id == id) else {
+            return;
+        };
+        let label = format!("unarchive '{}'", task.title);
+        task.unarchive();
+        self.commit(label, before);
+        self.archive_index = self
+            .archive_index
+            .min(self.archived_tasks().len().saturating_sub(1));
+    }
+
+    pub fn open_trash(&mut self) {
+        self.trash_index = self.trash.len().saturating_sub(1);
+        self.input_mode = InputMode::Trash;
+    }
+
+    /// True while the trash is on screen, including when it waits for a purge confirmation.
+    pub fn showing_trash(&self) -> bool {
+        matches!(self.input_mode, InputMode::Trash)
+            || matches!(
+                self.confirm,
+                Some(Confirm {
+                    action: PendingAction::PurgeTask { .. },
+                    ..
+                })
+            )
+    }
+
+    /// Puts the highlighted trashed task back in the column it was deleted from.
+    pub fn restore_trashed(&mut self) {
+        let Some(trashed) = self.trash.get(self.trash_index) else {
+            return;
+        };
+        let task = trashed.task.clone();
+        let before = self.all_tasks.clone();
+        self.trash.remove(self.trash_index);
+        self.trash_index = self.trash_index.min(self.trash.len().saturating_sub(1));
+        // A leftover entry for a task that is already back (e.g. from another window) just goes.
+        if self.all_tasks.iter().any(|t| t.id == task.id) {
+            self.persist();
+            self.status_message = Some(format!("'{}' is already on the board", task.title));
+            return;
+        }
+        let label = format!("restore '{}'", task.title);
+        self.all_tasks.push(task);
+        self.commit(label, before);
+    }
+
+    /// Asks before removing the highlighted trashed task for good.
+    pub fn purge_trashed(&mut self) {
+        let Some(trashed) = self.trash.get(self.trash_index) else {
+            return;
+        };
+        self.confirm = Some(Confirm {
+            prompt: format!(
+                "Delete '{}' permanently? This cannot be undone.",
+                trashed.task.title
+            ),
+            action: PendingAction::PurgeTask {
+                id: trashed.task.id,
+            },
+        });
+        self.input_mode = InputMode::Confirm;
     }
 
     pub fn start_edit(&mut self) {
@@ -153,10 +516,12 @@ impl App {
             let id = task.id;
             let context = task.context.clone();
             let priority = task.priority.clone();
+            let urgency = task.urgency.clone();
             let description = task.description.clone();
             self.input = title;
             self.editing_context = context;
             self.editing_priority = priority;
+            self.editing_urgency = urgency;
             self.editing_description = description.unwrap_or_default();
             self.input_mode = InputMode::Editing;
             self.is_editing_existing = true;
@@ -165,74 +530,550 @@ impl App {
     }
 
     pub fn get_current_column_tasks(&self) -> Vec<&Task> {
-        let status = match self.column_index {
-            0 => TaskStatus::Idea,
-            1 => TaskStatus::Todo,
-            2 => TaskStatus::Doing,
-            _ => TaskStatus::Done,
-        };
-        self.tasks_by_status(status)
+        match self.config.columns.get(self.column_index) {
+            Some(column) => self.tasks_by_status(&column.status()),
+            None => Vec::new(),
+        }
     }
 
     pub fn move_task_forward(&mut self) {
+        self.shift_selected_task(1);
+    }
+
+    pub fn move_task_backward(&mut self) {
+        self.shift_selected_task(-1);
+    }
+
+    /// Moves the selected task `step` columns along the board, stopping at either end.
+    /// Moving into a full column asks first when the board enforces WIP limits.
+    fn shift_selected_task(&mut self, step: isize) {
         let current_tasks = self.get_current_column_tasks();
-        if let Some(task_to_move) = current_tasks.get(self.selected_task_index) {
-            let id = task_to_move.id;
-            if let Some(task) = self.all_tasks.iter_mut().find(|t| t.id == id) {
-                task.status = match task.status {
-                    TaskStatus::Idea => TaskStatus::Todo,
-                    TaskStatus::Todo => TaskStatus::Doing,
-                    TaskStatus::Doing => TaskStatus::Done,
-                    TaskStatus::Done => TaskStatus::Done,
-                };
+        let Some(id) = current_tasks.get(self.selected_task_index).map(|t| t.id) else {
+            return;
+        };
+        let last = self.config.columns.len() - 1;
+        let target = self.column_index.saturating_add_signed(step).min(last);
+        if target == self.column_index {
+            return;
+        }
+
+        if self.config.enforce_wip_limits && self.config.is_full(target, &self.all_tasks) {
+            let column = &self.config.columns[target];
+            self.confirm = Some(Confirm {
+                prompt: format!(
+                    "{} is at its limit of {}. Move it there anyway?",
+                    column.name,
+                    column.wip_limit.unwrap_or_default()
+                ),
+                action: PendingAction::MoveTask { id, target },
+            });
+            self.input_mode = InputMode::Confirm;
+            return;
+        }
+        self.move_task_to(id, target);
+    }
+
+    fn move_task_to(&mut self, id: uuid::Uuid, target: usize) {
+        let column = &self.config.columns[target];
+        let status = column.status();
+        let before = self.all_tasks.clone();
+        let Some(task) = self.all_tasks.iter_mut().find(|t| t.id == id) else {
+            return;
+        };
+        let label = format!("move '{}' to {}", task.title, column.name);
+        task.move_to(status.clone(), &self.config);
+        // Arriving cards go to the bottom of the column.
+        let rank = data::bottom_rank(&before, &status);
+        if let Some(task) = self.all_tasks.iter_mut().find(|t| t.id == id) {
+            task.rank = rank;
+        }
+        self.commit(label, before);
+        self.clamp_selection();
+    }
+
+    /// Runs the action waiting for confirmation.
+    pub fn confirm_pending(&mut self) {
+        self.input_mode = InputMode::Normal;
+        if let Some(confirm) = self.confirm.take() {
+            match confirm.action {
+                PendingAction::MoveTask { id, target } => self.move_task_to(id, target),
+                PendingAction::DeleteTask { id } => self.trash_task(id),
+                PendingAction::PurgeTask { id } => {
+                    self.trash.retain(|t| t.task.id != id);
+                    self.trash_index = self.trash_index.min(self.trash.len().saturating_sub(1));
+                    self.persist();
+                    self.input_mode = InputMode::Trash;
+                }
             }
         }
-        self.persist();
     }
 
-    pub fn move_task_backward(&mut self) {
-        let current_tasks = self.get_current_column_tasks();
-        if let Some(task_to_move) = current_tasks.get(self.selected_task_index) {
-            let id = task_to_move.id;
-            if let Some(task) = self.all_tasks.iter_mut().find(|t| t.id == id) {
-                task.status = match task.status {
-                    TaskStatus::Idea => TaskStatus::Idea,
-                    TaskStatus::Todo => TaskStatus::Idea,
-                    TaskStatus::Doing => TaskStatus::Todo,
-                    TaskStatus::Done => TaskStatus::Doing,
+    pub fn cancel_pending(&mut self) {
+        self.input_mode = if self.showing_trash() {
+            InputMode::Trash
+        } else {
+            InputMode::Normal
+        };
+        self.confirm = None;
+    }
+
+    /// Number of tasks in column `index` across all contexts, which is what WIP limits count.
+    pub fn column_load(&self, index: usize) -> usize {
+        self.config.columns.get(index).map_or(0, |column| {
+            self.all_tasks
+                .iter()
+                .filter(|t| !t.is_archived() && t.status.0 == column.id)
+                .count()
+        })
+    }
+
+    pub fn tasks_by_status(&self, status: &TaskStatus) -> Vec<&Task> {
+        let now = Utc::now();
+        let mut tasks: Vec<&Task> = self
+            .all_tasks
+            .iter()
+            .filter(|t| !t.is_archived() && &t.status == status)
+            .filter(|t| self.current_context == "All" || t.context == self.current_context)
+            .filter(|t| t.matches_fuzzy(&self.search_query))
+            .filter(|t| {
+                self.active_view
+                    .as_ref()
+                    .is_none_or(|(_, query)| query.matches(t, &self.config, now))
+            })
+            .collect();
+
+        self.config.sort_mode(status).sort(&mut tasks);
+        tasks
+    }
+
+    pub fn open_view_picker(&mut self) {
+        if self.config.views.is_empty() {
+            self.status_message =
+                Some("No saved views; add one with `myeon views add <name> <filter>`".to_string());
+            return;
+        }
+        self.view_index = self
+            .active_view
+            .as_ref()
+            .and_then(|(name, _)| self.config.views.iter().position(|v| &v.name == name))
+            .map_or(0, |i| i + 1);
+        self.input_mode = InputMode::ViewPicker;
+    }
+
+    /// Filters the board by the view highlighted in the picker.
+    pub fn select_view(&mut self) {
+        self.input_mode = InputMode::Normal;
+        self.active_view = match self.view_index.checked_sub(1) {
+            None => None,
+            Some(index) => {
+                let Some(view) = self.config.views.get(index) else {
+                    return;
                 };
+                match view
+                    .query
+                    .parse::<Query>()
+                    .and_then(|query| query.check(&self.config).map(|()| query))
+                {
+                    Ok(query) => Some((view.name.clone(), query)),
+                    Err(e) => {
+                        self.status_message = Some(format!("View '{}': {}", view.name, e));
+                        return;
+                    }
+                }
+            }
+        };
+        self.focus_filtered();
+    }
+
+    /// Opens the search prompt, keeping any filter already typed.
+    pub fn start_search(&mut self) {
+        self.input_mode = InputMode::Search;
+    }
+
+    /// Changes the search filter and moves to the first card still showing.
+    pub fn set_search(&mut self, query: String) {
+        self.search_query = query;
+        self.focus_filtered();
+    }
+
+    /// Selects the top card of the current column, or of the first column with
+    /// any cards left if the filter emptied the current one.
+    fn focus_filtered(&mut self) {
+        if self.get_current_column_tasks().is_empty()
+            && let Some(column) = (0..self.config.columns.len()).find(|&i| {
+                !self
+                    .tasks_by_status(&self.config.columns[i].status())
+                    .is_empty()
+            })
+        {
+            self.column_index = column;
+        }
+        self.selected_task_index = 0;
+    }
+
+    pub fn clear_search(&mut self) {
+        self.set_search(String::new());
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Selects the next (`forward`) or previous card on the board, reading down
+    /// each column and then across, wrapping at the ends. With a search active
+    /// these are the matches.
+    pub fn jump_to_match(&mut self, forward: bool) {
+        let cards: Vec<(usize, usize)> = self
+            .config
+            .columns
+            .iter()
+            .enumerate()
+            .flat_map(|(c, column)| {
+                (0..self.tasks_by_status(&column.status()).len()).map(move |i| (c, i))
+            })
+            .collect();
+        if cards.is_empty() {
+            self.status_message = Some("No matches".to_string());
+            return;
+        }
+        let current = (self.column_index, self.selected_task_index);
+        let next = if forward {
+            cards
+                .iter()
+                .find(|&&card| card > current)
+                .unwrap_or(&cards[0])
+        } else {
+            cards
+                .iter()
+                .rev()
+                .find(|&&card| card < current)
+                .unwrap_or(&cards[cards.len() - 1])
+        };
+        (self.column_index, self.selected_task_index) = *next;
+    }
+
+    /// Moves the selected card `step` places up (negative) or down its column,
+    /// when the column is sorted by hand.
+    pub fn move_card(&mut self, step: isize) {
+        let Some(column) = self.config.columns.get(self.column_index) else {
+            return;
+        };
+        if !column.sort.is_manual() {
+            self.status_message = Some(format!(
+                "{} is sorted {}; press o until it is sorted by hand",
+                column.name,
+                column.sort.describe()
+            ));
+            return;
+        }
+        let status = column.status();
+        let visible: Vec<uuid::Uuid> = self
+            .get_current_column_tasks()
+            .iter()
+            .map(|t| t.id)
+            .collect();
+        let Some(&id) = visible.get(self.selected_task_index) else {
+            return;
+        };
+        let Some((target, &neighbour)) = self
+            .selected_task_index
+            .checked_add_signed(step)
+            .and_then(|i| visible.get(i).map(|id| (i, id)))
+        else {
+            return;
+        };
+
+        let before = self.all_tasks.clone();
+        self.spread_ranks(&status);
+        let rank = |tasks: &[Task], id| tasks.iter().find(|t| t.id == id).map(|t| t.rank);
+        let (Some(own), Some(other)) =
+            (rank(&self.all_tasks, id), rank(&self.all_tasks, neighbour))
+        else {
+            return;
+        };
+        let mut label = String::new();
+        for task in self.all_tasks.iter_mut() {
+            if task.id == id {
+                task.rank = other;
+                label = format!("reorder '{}'", task.title);
+            } else if task.id == neighbour {
+                task.rank = own;
+            }
+        }
+        self.selected_task_index = target;
+        self.commit(label, before);
+    }
+
+    /// Gives every card in the column its own rank, keeping the current order,
+    /// if some still share one. After that a move only swaps two ranks.
+    fn spread_ranks(&mut self, status: &TaskStatus) {
+        let mut column: Vec<&Task> = self
+            .all_tasks
+            .iter()
+            .filter(|t| !t.is_archived() && &t.status == status)
+            .collect();
+        let mut ranks: Vec<i64> = column.iter().map(|t| t.rank).collect();
+        ranks.sort_unstable();
+        ranks.dedup();
+        if ranks.len() == column.len() {
+            return;
+        }
+        SortMode::Manual.sort(&mut column);
+        let order: Vec<uuid::Uuid> = column.iter().map(|t| t.id).collect();
+        for task in self.all_tasks.iter_mut() {
+            if let Some(position) = order.iter().position(|&id| id == task.id) {
+                task.rank = (position as i64 + 1) * data::RANK_GAP;
             }
         }
+    }
+
+    /// Switches the current column to the next sort mode.
+    pub fn cycle_sort(&mut self) {
+        let Some(column) = self.config.columns.get_mut(self.column_index) else {
+            return;
+        };
+        column.sort = column.sort.next();
+        self.status_message = Some(format!(
+            "{} now sorted {}",
+            column.name,
+            column.sort.describe()
+        ));
         self.persist();
+        self.clamp_selection();
+    }
+
+    pub fn open_matrix(&mut self) {
+        self.matrix_quadrant = 0;
+        self.matrix_index = 0;
+        self.input_mode = InputMode::Matrix;
     }
 
-    pub fn tasks_by_status(&self, status: TaskStatus) -> Vec<&Task> {
+    /// Unfinished tasks in one quadrant of the Eisenhower matrix: 0 is important
+    /// and urgent, 1 important, 2 urgent, 3 neither. Medium counts as important or urgent.
+    pub fn matrix_tasks(&self, quadrant: usize) -> Vec<&Task> {
         let mut tasks: Vec<&Task> = self
             .all_tasks
             .iter()
-            .filter(|t| t.status == status)
+            .filter(|t| !t.is_archived() && !self.config.is_done(&t.status))
             .filter(|t| self.current_context == "All" || t.context == self.current_context)
+            .filter(|t| matrix_quadrant(t) == quadrant)
             .collect();
+        tasks.sort_by_key(|t| t.importance());
+        tasks
+    }
 
-        tasks.sort_by(|a, b| {
-            let priority_order = |p: &Priority| match p {
-                Priority::High => 0,
-                Priority::Medium => 1,
-                Priority::Low => 2,
-            };
-            priority_order(&a.priority).cmp(&priority_order(&b.priority))
-            // Need Urgency later to use Eisenhower matrix
-            // .then_with(|| a.urgency.cmp(&b.urgency))
+    /// Moves the selected matrix card one quadrant over, by making it (not)
+    /// important or (not) urgent. `row` and `column` are -1, 0 or 1.
+    pub fn shift_in_matrix(&mut self, row: isize, column: isize) {
+        let Some(id) = self
+            .matrix_tasks(self.matrix_quadrant)
+            .get(self.matrix_index)
+            .map(|t| t.id)
+        else {
+            return;
+        };
+        let target_row = (self.matrix_quadrant / 2).saturating_add_signed(row).min(1);
+        let target_column = (self.matrix_quadrant % 2)
+            .saturating_add_signed(column)
+            .min(1);
+        let target = target_row * 2 + target_column;
+        if target == self.matrix_quadrant {
+            return;
+        }
+
+        let before = self.all_tasks.clone();
+        let Some(task) = self.all_tasks.iter_mut().find(|t| t.id == id) else {
+            return;
+        };
+        let important = target_row == 0;
+        if important != (task.priority != Priority::Low) {
+            task.apply(TaskEdit {
+                priority: Some(if important {
+                    Priority::High
+                } else {
+                    Priority::Low
+                }),
+                ..TaskEdit::default()
+            });
+        }
+        let urgent = target_column == 0;
+        if urgent != (task.urgency != Urgency::Low) {
+            task.apply(TaskEdit {
+                urgency: Some(if urgent { Urgency::High } else { Urgency::Low }),
+                ..TaskEdit::default()
+            });
+        }
+        let label = format!("move '{}' to {}", task.title, MATRIX_QUADRANTS[target]);
+        self.commit(label, before);
+
+        self.matrix_quadrant = target;
+        self.matrix_index = self
+            .matrix_tasks(target)
+            .iter()
+            .position(|t| t.id == id)
+            .unwrap_or(0);
+    }
+
+    /// Picks up changes written to the board file by something else (a script,
+    /// the ilseon export, another myeon) and merges them in by task id,
+    /// keeping the selected card selected.
+    pub fn reload_if_changed(&mut self) {
+        if self.recovery.is_some() {
+            return;
+        }
+        let modified = MyeonData::modified(&self.board_path);
+        if modified.is_none() || modified == self.synced_modified {
+            return;
+        }
+        self.synced_modified = modified;
+
+        // A file that does not parse may be mid-edit by hand; wait for the next change.
+        let Ok(on_disk) = MyeonData::read(&self.board_path) else {
+            return;
+        };
+        if on_disk.revision == self.synced.revision && on_disk.same_content(&self.synced) {
+            return;
+        }
+
+        let selected_id = self
+            .get_current_column_tasks()
+            .get(self.selected_task_index)
+            .map(|t| t.id);
+        let mut ours = self.snapshot();
+        data::merge(&self.synced, &mut ours, &on_disk);
+        self.config = ours.config;
+        self.all_tasks = ours.tasks;
+        self.trash = ours.trash;
+        self.trash_index = self.trash_index.min(self.trash.len().saturating_sub(1));
+        self.synced = on_disk;
+        self.column_index = self.column_index.min(self.config.columns.len() - 1);
+
+        let column_tasks = self.get_current_column_tasks();
+        self.selected_task_index = selected_id
+            .and_then(|id| column_tasks.iter().position(|t| t.id == id))
+            .unwrap_or(self.selected_task_index)
+            .min(column_tasks.len().saturating_sub(1));
+        self.status_message = Some("Reloaded changes from disk".to_string());
+    }
+
+    /// Saves a change made to `all_tasks` since `before` and records it for undo.
+    fn commit(&mut self, label: String, before: Vec<Task>) {
+        let ids = history::changed_ids(&before, &self.all_tasks);
+        self.sync_trash(&before);
+        self.persist();
+        self.history.record(label, &before, &self.all_tasks, &ids);
+        self.save_history();
+    }
+
+    pub fn undo(&mut self) {
+        // Compare the step against the board as it is on disk, not as we last saw it.
+        self.reload_if_changed();
+        let before = self.all_tasks.clone();
+        let outcome = self.history.undo(&mut self.all_tasks);
+        self.finish_history_step(outcome, &before, "Undid", "undo");
+    }
+
+    pub fn redo(&mut self) {
+        self.reload_if_changed();
+        let before = self.all_tasks.clone();
+        let outcome = self.history.redo(&mut self.all_tasks);
+        self.finish_history_step(outcome, &before, "Redid", "redo");
+    }
+
+    /// Saves the result of an undo or redo and reports it in the status line.
+    fn finish_history_step(
+        &mut self,
+        outcome: history::Outcome,
+        before: &[Task],
+        done: &str,
+        action: &str,
+    ) {
+        self.status_message = Some(match outcome {
+            history::Outcome::Applied(label) => {
+                self.sync_trash(before);
+                self.persist();
+                self.save_history();
+                format!("{} {}", done, label)
+            }
+            history::Outcome::Stale(label) => {
+                self.save_history();
+                format!(
+                    "Could not {} {}: its tasks were changed since",
+                    action, label
+                )
+            }
+            history::Outcome::Empty => format!("Nothing to {}", action),
         });
+        self.clamp_selection();
+    }
 
-        tasks
+    /// Sends tasks that were in `before` but are gone now to the trash, so that
+    /// nothing leaves the board without passing through it, and drops trash
+    /// entries for tasks that are back on the board, e.g. after undoing a delete.
+    fn sync_trash(&mut self, before: &[Task]) {
+        let tasks = &self.all_tasks;
+        self.trash
+            .retain(|trashed| !tasks.iter().any(|t| t.id == trashed.task.id));
+        for task in before {
+            if !self.all_tasks.iter().any(|t| t.id == task.id) {
+                self.trash.push(TrashedTask::new(task.clone()));
+            }
+        }
+        self.trash_index = self.trash_index.min(self.trash.len().saturating_sub(1));
     }
 
-    fn persist(&self) {
-        let data = MyeonData {
+    fn save_history(&mut self) {
+        if self.recovery.is_some() {
+            return;
+        }
+        if let Err(e) = self.history.save(&self.board_path) {
+            self.status_message = Some(format!("Could not save undo history: {}", e));
+        }
+    }
+
+    fn clamp_selection(&mut self) {
+        let count = self.get_current_column_tasks().len();
+        self.selected_task_index = self.selected_task_index.min(count.saturating_sub(1));
+    }
+
+    fn persist(&mut self) {
+        if self.recovery.is_some() {
+            return;
+        }
+        match self.snapshot().save_synced(&self.board_path, &self.synced) {
+            Ok(outcome) => {
+                if outcome.conflicts > 0 {
+                    self.status_message = Some(format!(
+                        "Board was changed in another window; kept your version of {} task(s)",
+                        outcome.conflicts
+                    ));
+                } else if outcome.merged {
+                    self.status_message = Some("Merged changes made in another window".to_string());
+                }
+                self.config = outcome.data.config.clone();
+                self.all_tasks = outcome.data.tasks.clone();
+                self.trash = outcome.data.trash.clone();
+                self.column_index = self.column_index.min(self.config.columns.len() - 1);
+                self.synced = outcome.data;
+                self.synced_modified = MyeonData::modified(&self.board_path);
+            }
+            // The file went bad under us: stop taking edits until the user decides.
+            Err(
+                e @ (AppError::CorruptData { .. }
+                | AppError::Quarantined { .. }
+                | AppError::UnsupportedSchema { .. }),
+            ) => self.enter_recovery(e),
+            Err(e) => self.status_message = Some(format!("Could not save: {}", e)),
+        }
+    }
+
+    /// The board as this instance currently sees it.
+    fn snapshot(&self) -> MyeonData {
+        MyeonData {
+            revision: self.synced.revision,
+            next_task_number: self.synced.next_task_number,
+            config: self.config.clone(),
             tasks: self.all_tasks.clone(),
-        };
-        let _ = data.save();
+            trash: self.trash.clone(),
+            ..MyeonData::default()
+        }
     }
 
     fn reset_editing_state(&mut self) {
@@ -240,8 +1081,118 @@ impl App {
         self.editing_context.clear();
         self.editing_description.clear();
         self.editing_priority = Priority::Low;
+        self.editing_urgency = Urgency::Low;
         self.active_edit_field = EditField::Title;
         self.context_list_index = 0;
         self.input_mode = InputMode::Normal;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+
+    /// A board with one task in a fresh temporary directory.
+    fn board(name: &str) -> (App, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("myeon-{}-{}", name, uuid::Uuid::new_v4()));
+        let path = dir.join("tasks.json");
+        let mut data = MyeonData::default();
+        let status = data.config.first_status();
+        data.add_task(Task::new("Write tests", status, Priority::Low, None, None));
+        data.save(&path).unwrap();
+        (App::new(path.clone()), path)
+    }
+
+    /// Removes the board's directory, side files included (see `storage::side_base`).
+    fn clean_up(path: &Path) {
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn undoing_a_delete_takes_the_task_out_of_the_trash() {
+        let (mut app, path) = board("undo-delete");
+        app.delete_task();
+        app.confirm_pending();
+        assert!(app.all_tasks.is_empty());
+        assert_eq!(app.trash.len(), 1);
+
+        app.undo();
+        assert_eq!(app.all_tasks.len(), 1);
+        assert!(app.trash.is_empty());
+
+        app.open_trash();
+        app.restore_trashed();
+        assert_eq!(app.all_tasks.len(), 1);
+
+        app.redo();
+        assert!(app.all_tasks.is_empty());
+        assert_eq!(app.trash.len(), 1);
+
+        let saved = MyeonData::load(&path).unwrap();
+        assert!(saved.tasks.is_empty());
+        assert_eq!(saved.trash.len(), 1);
+        clean_up(&path);
+    }
+
+    #[test]
+    fn restoring_a_task_already_on_the_board_does_not_duplicate_it() {
+        let (mut app, path) = board("restore-twice");
+        let task = app.all_tasks[0].clone();
+        app.trash.push(TrashedTask::new(task));
+
+        app.open_trash();
+        app.restore_trashed();
+
+        assert_eq!(app.all_tasks.len(), 1);
+        assert!(app.trash.is_empty());
+        assert_eq!(MyeonData::load(&path).unwrap().tasks.len(), 1);
+        clean_up(&path);
+    }
+
+    #[test]
+    fn a_board_broken_while_open_stops_the_next_save() {
+        let (mut app, path) = board("broken-while-open");
+        fs::write(&path, "{ not json").unwrap();
+
+        app.all_tasks[0].title = "Edited".to_string();
+        app.persist();
+        assert!(app.recovery.is_some());
+        assert!(!path.exists());
+
+        app.persist();
+        assert!(!path.exists());
+        clean_up(&path);
+    }
+
+    #[test]
+    fn a_board_from_a_newer_myeon_is_never_replaced() {
+        let (mut app, path) = board("newer-schema");
+        let newer = r#"{"schema_version": 99, "tasks": []}"#;
+        fs::write(&path, newer).unwrap();
+
+        app.persist();
+        assert!(app.recovery.as_ref().is_some_and(|r| !r.can_start_fresh));
+        app.start_fresh();
+        assert!(app.recovery.is_some());
+        assert_eq!(fs::read_to_string(&path).unwrap(), newer);
+        clean_up(&path);
+    }
+
+    #[test]
+    fn moving_the_last_card_away_keeps_the_selection_on_the_column() {
+        let (mut app, path) = board("move-last");
+        let status = app.config.first_status();
+        app.all_tasks
+            .push(Task::new("Second", status, Priority::Low, None, None));
+        app.selected_task_index = 1;
+
+        app.move_task_forward();
+        assert_eq!(app.selected_task_index, 0);
+
+        app.move_card(-1);
+        assert_eq!(app.get_current_column_tasks().len(), 1);
+        clean_up(&path);
+    }
+}
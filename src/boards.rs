@@ -0,0 +1,154 @@
+use crate::data::MyeonData;
+use crate::error::AppError;
+use crate::storage;
+use directories::ProjectDirs;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name shown for the user-level board in the config directory.
+pub const DEFAULT_BOARD: &str = "default";
+
+/// A board that can be opened by name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoardEntry {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// Named boards, each kept as `<name>.json` in the `boards` folder of the
+/// myeon data directory (e.g. ~/.local/share/myeon/boards on Linux).
+pub struct BoardRegistry {
+    dir: PathBuf,
+}
+
+impl BoardRegistry {
+    pub fn open() -> Result<Self, AppError> {
+        let proj_dirs = ProjectDirs::from("com", "ilseon", "myeon").ok_or(AppError::NoBoardPath)?;
+        Ok(Self {
+            dir: proj_dirs.data_dir().join("boards"),
+        })
+    }
+
+    /// All boards, starting with the user-level default board.
+    pub fn list(&self) -> Vec<BoardEntry> {
+        let mut boards: Vec<BoardEntry> = fs::read_dir(&self.dir)
+            .map(|entries| {
+                entries
+                    .filter_map(Result::ok)
+                    .map(|entry| entry.path())
+                    .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                    .filter_map(|path| {
+                        let name = path.file_stem()?.to_str()?.to_string();
+                        Some(BoardEntry { name, path })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        boards.sort_by(|a, b| a.name.cmp(&b.name));
+
+        if let Some(path) = MyeonData::user_board_path() {
+            boards.insert(
+                0,
+                BoardEntry {
+                    name: DEFAULT_BOARD.to_string(),
+                    path,
+                },
+            );
+        }
+        boards
+    }
+
+    pub fn find(&self, name: &str) -> Option<BoardEntry> {
+        self.list().into_iter().find(|b| b.name == name)
+    }
+
+    pub fn create(&self, name: &str) -> Result<BoardEntry, AppError> {
+        let path = self.path_for(name)?;
+        if self.find(name).is_some() {
+            return Err(AppError::Board(format!(
+                "a board named '{}' already exists",
+                name
+            )));
+        }
+        MyeonData::default().save(&path)?;
+        Ok(BoardEntry {
+            name: name.to_string(),
+            path,
+        })
+    }
+
+    /// Renames a board file together with its backups and undo journal.
+    pub fn rename(&self, from: &str, to: &str) -> Result<BoardEntry, AppError> {
+        let board = self.find_named(from)?;
+        let target = self.path_for(to)?;
+        if self.find(to).is_some() {
+            return Err(AppError::Board(format!(
+                "a board named '{}' already exists",
+                to
+            )));
+        }
+        let lock = storage::lock(&board.path)?;
+        fs::rename(&board.path, &target)?;
+        for backup in storage::list_backups(&board.path) {
+            fs::rename(&backup.path, storage::backup_path(&target, backup.index))?;
+        }
+        let journal = storage::journal_path(&board.path);
+        if journal.exists() {
+            fs::rename(&journal, storage::journal_path(&target))?;
+        }
+        drop(lock);
+        let _ = fs::remove_file(storage::lock_path(&board.path));
+        Ok(BoardEntry {
+            name: to.to_string(),
+            path: target,
+        })
+    }
+
+    /// Deletes a board file together with its backups, undo journal and lock file.
+    pub fn delete(&self, name: &str) -> Result<BoardEntry, AppError> {
+        let board = self.find_named(name)?;
+        for backup in storage::list_backups(&board.path) {
+            fs::remove_file(&backup.path)?;
+        }
+        fs::remove_file(&board.path)?;
+        let _ = fs::remove_file(storage::journal_path(&board.path));
+        let _ = fs::remove_file(storage::lock_path(&board.path));
+        Ok(board)
+    }
+
+    /// Like `find`, but only for named boards: the default board cannot be renamed or deleted.
+    fn find_named(&self, name: &str) -> Result<BoardEntry, AppError> {
+        if name == DEFAULT_BOARD {
+            return Err(AppError::Board(format!(
+                "the '{}' board cannot be renamed or deleted",
+                DEFAULT_BOARD
+            )));
+        }
+        self.find(name)
+            .ok_or_else(|| AppError::Board(format!("no board named '{}'", name)))
+    }
+
+    fn path_for(&self, name: &str) -> Result<PathBuf, AppError> {
+        let valid = !name.is_empty()
+            && name != DEFAULT_BOARD
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(AppError::Board(format!(
+                "'{}' is not a valid board name; use letters, digits, '-' and '_'",
+                name
+            )));
+        }
+        Ok(self.dir.join(format!("{}.json", name)))
+    }
+}
+
+/// Display name for a board path: its registry name if it has one, otherwise the path itself.
+pub fn display_name(path: &Path) -> String {
+    BoardRegistry::open()
+        .ok()
+        .and_then(|registry| registry.list().into_iter().find(|b| b.path == path))
+        .map(|b| b.name)
+        .unwrap_or_else(|| path.display().to_string())
+}
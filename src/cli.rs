@@ -1,4 +1,9 @@
+use crate::data::{Priority, SortMode, Urgency};
+use crate::output::{FlowFormat, OutputFormat};
+use crate::query::Query;
+use crate::stats;
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(
@@ -7,17 +12,259 @@ use clap::{Parser, Subcommand};
     version,
     about = "myeon is a minimalist, keyboard-driven TUI Kanban board",
     long_about = None,
-    after_help = "KEYBINDINGS:\n  h/j/k/l    Move focus across tasks and columns\n  a          Quick-capture a new idea\n  e          Edit a task\n  c          Change Context (cycle Work/Personal/etc.)\n  Enter      Move the task forward\n  Backspace  Move the task backward\n  d          Delete a task\n  q          Quit"
+    after_help = "KEYBINDINGS:\n  h/j/k/l    Move focus across tasks and columns\n  a          Quick-capture a new idea\n  e          Edit a task\n  i          Show task details and history\n  c          Change Context (cycle Work/Personal/etc.)\n  /          Search and filter the board (Esc clears)\n  n/N        Jump to the next/previous match\n  V          Filter by a saved view\n  Enter      Move the task forward\n  Backspace  Move the task backward\n  J/K        Move the card down/up its column\n  o          Cycle the column's sort order\n  d          Delete a task (to the trash)\n  t          Open the trash\n  A          Archive a task\n  v          Browse the archive\n  u          Undo\n  Ctrl-r     Redo\n  b          Switch board\n  s          Show statistics\n  f          Show the cumulative flow diagram\n  m          Open the Eisenhower matrix\n  q          Quit"
 )]
 #[command(propagate_version = true)]
 pub struct Cli {
+    /// Board file to open. Defaults to the nearest .myeon.json in this directory
+    /// or its parents, then the board in your config directory.
+    #[arg(long, global = true, env = "MYEON_BOARD", value_name = "PATH")]
+    pub board: Option<PathBuf>,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
+    /// Add a task without opening the board.
+    Add {
+        title: String,
+        #[arg(long)]
+        context: Option<String>,
+        /// high, medium or low.
+        #[arg(long, default_value = "low")]
+        priority: Priority,
+        /// high, medium or low.
+        #[arg(long, default_value = "low")]
+        urgency: Urgency,
+        #[arg(long = "desc")]
+        description: Option<String>,
+        /// Column to add it to. Defaults to the first column.
+        #[arg(long)]
+        column: Option<String>,
+    },
+    /// List tasks, optionally only those in one column or context.
+    List {
+        /// Column name or id.
+        #[arg(long)]
+        status: Option<String>,
+        #[arg(long)]
+        context: Option<String>,
+        /// Only tasks matching a filter such as `prio:high status:doing age>7d tag:bug "login"`.
+        #[arg(long)]
+        filter: Option<Query>,
+        /// Only tasks matching a saved view (see `myeon views`).
+        #[arg(long)]
+        view: Option<String>,
+        /// Output format. json, ndjson and tsv share a stable set of fields:
+        /// id, short_id, title, status, column, priority, context, created_at, urgency, number.
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
+    /// Move a task to another column.
+    Move {
+        /// Task number, or a unique start of its id.
+        task: String,
+        /// Column name or id.
+        column: String,
+        /// Move even if the board enforces WIP limits and the column is full.
+        #[arg(long)]
+        force: bool,
+    },
+    /// Change a task's title, description, context or priority.
+    Edit {
+        /// Task number, or a unique start of its id.
+        task: String,
+        #[arg(long)]
+        title: Option<String>,
+        #[arg(long = "desc")]
+        description: Option<String>,
+        #[arg(long)]
+        context: Option<String>,
+        #[arg(long)]
+        priority: Option<Priority>,
+        #[arg(long)]
+        urgency: Option<Urgency>,
+    },
+    /// Move a task to the trash.
+    Rm {
+        /// Task number, or a unique start of its id.
+        task: String,
+    },
+    /// List the automatic backups of the board, or restore one of them.
+    Restore {
+        /// Backup number to restore (1 is the most recent). Omit to list backups.
+        backup: Option<usize>,
+    },
+    /// Show or change the columns of the board.
+    Columns {
+        #[command(subcommand)]
+        action: ColumnsCommand,
+    },
+    /// Show throughput, lead and cycle time, WIP and ageing work.
+    Stats {
+        /// Only count tasks in this context.
+        #[arg(long)]
+        context: Option<String>,
+        /// Number of weeks of throughput to show.
+        #[arg(
+            long,
+            default_value_t = stats::DEFAULT_WEEKS,
+            value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..=stats::MAX_WEEKS)
+        )]
+        weeks: usize,
+    },
+    /// Show how many tasks were in each column on each day (a cumulative flow diagram).
+    Cfd {
+        /// Only count tasks in this context.
+        #[arg(long)]
+        context: Option<String>,
+        /// Number of weeks to go back.
+        #[arg(
+            long,
+            default_value_t = stats::DEFAULT_WEEKS,
+            value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..=stats::MAX_WEEKS)
+        )]
+        weeks: usize,
+        #[arg(long, value_enum, default_value_t)]
+        format: FlowFormat,
+    },
+    /// Browse, archive or restore finished work kept off the board.
+    Archive {
+        #[command(subcommand)]
+        action: ArchiveCommand,
+    },
+    /// Show, restore or empty the deleted tasks.
+    Trash {
+        #[command(subcommand)]
+        action: TrashCommand,
+    },
+    /// Save, list or remove named filters ("views").
+    Views {
+        #[command(subcommand)]
+        action: ViewsCommand,
+    },
+    /// Manage named boards.
+    Boards {
+        #[command(subcommand)]
+        action: BoardsCommand,
+    },
     /// Update myeon to the latest version.
     #[command(name = "update", hide = true)] // Hidden from help
     Update,
 }
+
+#[derive(Subcommand)]
+pub enum BoardsCommand {
+    /// List all boards. The one that would open here is marked with `*`.
+    List,
+    /// Create a new, empty board.
+    New { name: String },
+    /// Rename a board.
+    Rename { from: String, to: String },
+    /// Delete a board and its backups.
+    Delete {
+        name: String,
+        /// Confirm the deletion.
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ArchiveCommand {
+    /// List archived tasks, most recently archived first.
+    List {
+        /// Only tasks whose title, description or context contains this text.
+        #[arg(long)]
+        search: Option<String>,
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+    },
+    /// Archive a task.
+    Task {
+        /// Task number, or a unique start of its id.
+        task: String,
+    },
+    /// Archive every task in the last column.
+    Done,
+    /// Put an archived task back on the board.
+    Restore {
+        /// Task number, or a unique start of its id.
+        task: String,
+    },
+    /// Archive tasks automatically after this many days in the last column. 0 turns it off.
+    After { days: u32 },
+}
+
+#[derive(Subcommand)]
+pub enum TrashCommand {
+    /// List the tasks in the trash.
+    List,
+    /// Put a task back on the board, in the column it was deleted from.
+    Restore {
+        /// Task number, or a unique start of its id.
+        task: String,
+    },
+    /// Delete every task in the trash for good.
+    Empty {
+        /// Confirm emptying the trash.
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Set how many days deleted tasks are kept. 0 keeps them forever.
+    Keep { days: u32 },
+}
+
+#[derive(Subcommand)]
+pub enum ViewsCommand {
+    /// List the saved views.
+    List,
+    /// Save a filter under a name, replacing any view with that name.
+    Add {
+        name: String,
+        /// A filter such as `ctx:Work prio:high status:doing age>7d tag:bug "login"`.
+        query: Query,
+    },
+    /// Remove a saved view.
+    Remove { name: String },
+}
+
+#[derive(Subcommand)]
+pub enum ColumnsCommand {
+    /// List the columns in board order.
+    List,
+    /// Add a column.
+    Add {
+        name: String,
+        /// Position on the board, starting at 1. Defaults to the end.
+        #[arg(long)]
+        position: Option<usize>,
+    },
+    /// Rename a column. Its tasks stay in it.
+    Rename { column: String, name: String },
+    /// Move a column to another position, starting at 1.
+    Move { column: String, position: usize },
+    /// Remove an empty column.
+    Remove { column: String },
+    /// Set a column's soft WIP limit. Omit the limit to remove it.
+    Limit {
+        column: String,
+        limit: Option<usize>,
+    },
+    /// Show a quiet age cue on cards that sat in a column for at least these many days,
+    /// e.g. `3 7 14`. Without days, the cue is turned off.
+    Ageing { days: Vec<u32> },
+    /// Count work as started once it reaches this column, for cycle time and ageing
+    /// in `myeon stats`. Until set, that is the column before the last.
+    Start { column: String },
+    /// Order a column's cards by hand (`manual`, moved with J/K), `priority`, `created`,
+    /// `updated`, `title` or `time-in-column`.
+    Sort { column: String, sort: SortMode },
+    /// Ask before moving a task into a column at its limit (on/off).
+    EnforceLimits {
+        #[arg(action = clap::ArgAction::Set, value_parser = clap::builder::BoolishValueParser::new())]
+        enabled: bool,
+    },
+}
@@ -1,4 +1,5 @@
 use colored::*;
+use std::io::IsTerminal;
 
 /// Utility functions for printing messages in different colours
 /// for better visibility and user experience in the command line interface.
@@ -17,3 +18,30 @@ pub fn warn(message: &str) {
 pub fn error(message: &str) {
     eprintln!("{}", message.red().bold());
 }
+
+/// Leaves styling on only when stdout is a terminal or `CLICOLOR_FORCE` asks for it.
+/// `colored` already honours `NO_COLOR` and `CLICOLOR=0`.
+pub fn detect_for_stdout() {
+    let forced = std::env::var_os("CLICOLOR_FORCE").is_some_and(|v| !v.is_empty() && v != "0");
+    if !forced && !std::io::stdout().is_terminal() {
+        colored::control::set_override(false);
+    }
+}
+
+/// Styling for pieces of tabular output. The result is plain text
+/// when colours are turned off, e.g. because stdout is not a terminal.
+pub fn heading(text: &str) -> String {
+    text.bold().to_string()
+}
+
+pub fn muted(text: &str) -> String {
+    text.dimmed().to_string()
+}
+
+pub fn notice(text: &str) -> String {
+    text.yellow().to_string()
+}
+
+pub fn urgent(text: &str) -> String {
+    text.red().to_string()
+}
@@ -0,0 +1,686 @@
+use crate::boards::BoardRegistry;
+use crate::cli::{ArchiveCommand, BoardsCommand, ColumnsCommand, TrashCommand, ViewsCommand};
+use crate::colours;
+use crate::data::{self, BoardConfig, MyeonData, Priority, Task, TaskEdit, Urgency, View};
+use crate::error::AppError;
+use crate::output::{self, FlowFormat, OutputFormat, TaskRecord};
+use crate::query::Query;
+use crate::stats;
+use crate::storage;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Lists the rotating backups of the board, or restores backup `index` over it.
+/// The current board is itself rotated into the backups, so a restore can be undone.
+pub fn restore(path: &Path, index: Option<usize>) -> Result<(), AppError> {
+    let backups = storage::list_backups(path);
+
+    let Some(index) = index else {
+        if backups.is_empty() {
+            colours::info(
+                "No backups yet. They are taken when the board is saved, at most every ten minutes.",
+            );
+            return Ok(());
+        }
+        colours::info(&format!("Backups of {}:", path.display()));
+        for backup in &backups {
+            let modified = backup
+                .modified
+                .map(|t| {
+                    chrono::DateTime::<chrono::Local>::from(t)
+                        .format("%Y-%m-%d %H:%M:%S")
+                        .to_string()
+                })
+                .unwrap_or_else(|| "unknown".to_string());
+            let summary = match MyeonData::read(&backup.path) {
+                Ok(data) => format!("{} tasks", data.tasks.len()),
+                Err(_) => "unreadable".to_string(),
+            };
+            println!("  {}  {}  {}", backup.index, modified, summary);
+        }
+        println!("\nRun `myeon restore <n>` to restore one.");
+        return Ok(());
+    };
+
+    let backup = backups.iter().find(|b| b.index == index).ok_or_else(|| {
+        AppError::FileNotFound(storage::backup_path(path, index).display().to_string())
+    })?;
+    let mut data = MyeonData::read(&backup.path)?;
+    let _lock = storage::lock(path)?;
+    // Newer than the current board, so open instances pick the restore up as a change.
+    let current_revision = MyeonData::read(path).map(|d| d.revision).unwrap_or(0);
+    data.revision = current_revision.max(data.revision) + 1;
+    // Always keep the board being replaced, however recent the last backup is.
+    storage::rotate_backups(path)?;
+    data.save(path)?;
+    colours::success(&format!(
+        "Restored backup {} ({} tasks). The previous board is now backup 1.",
+        index,
+        data.tasks.len()
+    ));
+    Ok(())
+}
+
+/// Runs a `myeon boards` subcommand. `current` is the board that would open
+/// in this directory, if it could be determined.
+pub fn boards(action: BoardsCommand, current: Option<&Path>) -> Result<(), AppError> {
+    let registry = BoardRegistry::open()?;
+    match action {
+        BoardsCommand::List => {
+            for board in registry.list() {
+                let marker = if Some(board.path.as_path()) == current {
+                    "*"
+                } else {
+                    " "
+                };
+                println!("{} {:<16} {}", marker, board.name, board.path.display());
+            }
+        }
+        BoardsCommand::New { name } => {
+            let board = registry.create(&name)?;
+            colours::success(&format!(
+                "Created board '{}'. Open it with `myeon --board {}`.",
+                board.name, board.name
+            ));
+        }
+        BoardsCommand::Rename { from, to } => {
+            registry.rename(&from, &to)?;
+            colours::success(&format!("Renamed board '{}' to '{}'.", from, to));
+        }
+        BoardsCommand::Delete { name, yes } => {
+            if !yes {
+                colours::warn(&format!(
+                    "This deletes board '{}' and its backups. Run again with --yes to confirm.",
+                    name
+                ));
+                return Ok(());
+            }
+            registry.delete(&name)?;
+            colours::success(&format!("Deleted board '{}'.", name));
+        }
+    }
+    Ok(())
+}
+
+/// Runs a `myeon views` subcommand against the board at `path`.
+pub fn views(path: &Path, action: ViewsCommand) -> Result<(), AppError> {
+    match action {
+        ViewsCommand::List => {
+            let data = MyeonData::load_read_only(path)?;
+            if data.config.views.is_empty() {
+                colours::info("No saved views. Add one with `myeon views add <name> <filter>`.");
+            }
+            for view in &data.config.views {
+                println!("{:<20} {}", view.name, view.query);
+            }
+        }
+        ViewsCommand::Add { name, query } => {
+            let replaced = MyeonData::update(path, |data| {
+                query.check(&data.config).map_err(AppError::Board)?;
+                let view = View {
+                    name: name.clone(),
+                    query: query.to_string(),
+                };
+                let views = &mut data.config.views;
+                match views
+                    .iter()
+                    .position(|v| v.name.eq_ignore_ascii_case(&name))
+                {
+                    Some(index) => {
+                        views[index] = view;
+                        Ok(true)
+                    }
+                    None => {
+                        views.push(view);
+                        Ok(false)
+                    }
+                }
+            })?;
+            if replaced {
+                colours::success(&format!("Updated view '{}'.", name));
+            } else {
+                colours::success(&format!("Saved view '{}'.", name));
+            }
+        }
+        ViewsCommand::Remove { name } => {
+            MyeonData::update(path, |data| {
+                let views = &mut data.config.views;
+                let index = views
+                    .iter()
+                    .position(|v| v.name.eq_ignore_ascii_case(&name))
+                    .ok_or_else(|| AppError::Board(format!("no view named '{}'", name)))?;
+                views.remove(index);
+                Ok(())
+            })?;
+            colours::success(&format!("Removed view '{}'.", name));
+        }
+    }
+    Ok(())
+}
+
+/// Runs a `myeon columns` subcommand against the board at `path`.
+pub fn columns(path: &Path, action: ColumnsCommand) -> Result<(), AppError> {
+    match action {
+        ColumnsCommand::List => {
+            let data = MyeonData::load_read_only(path)?;
+            for (i, column) in data.config.columns.iter().enumerate() {
+                let count = data
+                    .tasks
+                    .iter()
+                    .filter(|t| !t.is_archived() && t.status.0 == column.id)
+                    .count();
+                let limit = column
+                    .wip_limit
+                    .map(|limit| format!(" (limit {})", limit))
+                    .unwrap_or_default();
+                println!("{:>2}. {:<20} {} tasks{}", i + 1, column.name, count, limit);
+            }
+            let in_progress = data.config.in_progress();
+            if !in_progress.is_empty() {
+                let start = &data.config.columns[in_progress.start];
+                println!("\nWork counts as started in {}.", start.name);
+            }
+            if data.config.enforce_wip_limits {
+                println!("Moving into a full column asks for confirmation.");
+            }
+        }
+        ColumnsCommand::Add { name, position } => {
+            let name = MyeonData::update(path, |data| {
+                let column = data
+                    .config
+                    .add_column(&name, position.map(|p| p.saturating_sub(1)));
+                Ok(column.name.clone())
+            })?;
+            colours::success(&format!("Added column '{}'.", name));
+        }
+        ColumnsCommand::Rename { column, name } => {
+            MyeonData::update(path, |data| {
+                let index = find_column(&data.config, &column)?;
+                data.config.columns[index].name = name.clone();
+                Ok(())
+            })?;
+            colours::success(&format!("Renamed column '{}' to '{}'.", column, name));
+        }
+        ColumnsCommand::Move { column, position } => {
+            MyeonData::update(path, |data| {
+                let index = find_column(&data.config, &column)?;
+                let moved = data.config.columns.remove(index);
+                let target = position.saturating_sub(1).min(data.config.columns.len());
+                data.config.columns.insert(target, moved);
+                Ok(())
+            })?;
+            colours::success(&format!("Moved column '{}'.", column));
+        }
+        ColumnsCommand::Remove { column } => {
+            MyeonData::update(path, |data| {
+                let index = find_column(&data.config, &column)?;
+                if data.config.columns.len() == 1 {
+                    return Err(AppError::Board("a board needs at least one column".into()));
+                }
+                let id = data.config.columns[index].id.clone();
+                let count = data
+                    .tasks
+                    .iter()
+                    .filter(|t| !t.is_archived() && t.status.0 == id)
+                    .count();
+                if count > 0 {
+                    return Err(AppError::Board(format!(
+                        "column '{}' still has {} tasks; move them first",
+                        column, count
+                    )));
+                }
+                data.config.columns.remove(index);
+                Ok(())
+            })?;
+            colours::success(&format!("Removed column '{}'.", column));
+        }
+        ColumnsCommand::Limit { column, limit } => {
+            MyeonData::update(path, |data| {
+                let index = find_column(&data.config, &column)?;
+                data.config.columns[index].wip_limit = limit;
+                Ok(())
+            })?;
+            match limit {
+                Some(limit) => colours::success(&format!(
+                    "Column '{}' now has a limit of {}.",
+                    column, limit
+                )),
+                None => colours::success(&format!("Removed the limit on column '{}'.", column)),
+            }
+        }
+        ColumnsCommand::Start { column } => {
+            let name = MyeonData::update(path, |data| {
+                let index = find_column(&data.config, &column)?;
+                let column = &data.config.columns[index];
+                data.config.start_column = Some(column.id.clone());
+                Ok(column.name.clone())
+            })?;
+            colours::success(&format!("Work now counts as started in '{}'.", name));
+        }
+        ColumnsCommand::EnforceLimits { enabled } => {
+            MyeonData::update(path, |data| {
+                data.config.enforce_wip_limits = enabled;
+                Ok(())
+            })?;
+            if enabled {
+                colours::success("Moving a task into a full column now asks first.");
+            } else {
+                colours::success("WIP limits are shown but no longer ask before moving.");
+            }
+        }
+        ColumnsCommand::Ageing { mut days } => {
+            days.retain(|&d| d > 0);
+            days.sort_unstable();
+            days.dedup();
+            let message = if days.is_empty() {
+                "Cards no longer show how long they have been waiting.".to_string()
+            } else {
+                format!(
+                    "Cards now show a quiet age cue after {} days in a column.",
+                    days.iter()
+                        .map(u32::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            };
+            MyeonData::update(path, |data| {
+                data.config.ageing_days = days;
+                Ok(())
+            })?;
+            colours::success(&message);
+        }
+        ColumnsCommand::Sort { column, sort } => {
+            let name = MyeonData::update(path, |data| {
+                let index = find_column(&data.config, &column)?;
+                data.config.columns[index].sort = sort;
+                Ok(data.config.columns[index].name.clone())
+            })?;
+            colours::success(&format!(
+                "Column '{}' is now sorted {}.",
+                name,
+                sort.describe()
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn find_column(config: &BoardConfig, reference: &str) -> Result<usize, AppError> {
+    config
+        .find_column(reference)
+        .ok_or_else(|| AppError::Board(format!("no column named '{}'", reference)))
+}
+
+pub fn add(
+    path: &Path,
+    title: String,
+    context: Option<String>,
+    priority: Priority,
+    urgency: Urgency,
+    description: Option<String>,
+    column: Option<String>,
+) -> Result<(), AppError> {
+    let task = MyeonData::update(path, |data| {
+        let status = match &column {
+            Some(column) => data.config.columns[find_column(&data.config, column)?].status(),
+            None => data.config.first_status(),
+        };
+        let rank = data::bottom_rank(&data.tasks, &status);
+        let mut task = Task::new(title, status, priority, context, description);
+        task.urgency = urgency;
+        task.rank = rank;
+        Ok(data.add_task(task).clone())
+    })?;
+    colours::success(&format!("Added #{} {}", display_ref(&task), task.title));
+    Ok(())
+}
+
+pub fn list(
+    path: &Path,
+    status: Option<String>,
+    context: Option<String>,
+    filter: Option<Query>,
+    view: Option<String>,
+    format: OutputFormat,
+) -> Result<(), AppError> {
+    let data = MyeonData::load_read_only(path)?;
+    let column = status.map(|s| find_column(&data.config, &s)).transpose()?;
+    let view = view
+        .map(|name| {
+            let view = data
+                .config
+                .find_view(&name)
+                .ok_or_else(|| AppError::Board(format!("no view named '{}'", name)))?;
+            let query = view
+                .query
+                .parse::<Query>()
+                .map_err(|e| AppError::ParseError(format!("view '{}': {}", view.name, e)))?;
+            query
+                .check(&data.config)
+                .map_err(|e| AppError::Board(format!("view '{}': {}", view.name, e)))?;
+            Ok::<_, AppError>(query)
+        })
+        .transpose()?;
+    if let Some(filter) = &filter {
+        filter.check(&data.config).map_err(AppError::Board)?;
+    }
+    let now = chrono::Utc::now();
+
+    let mut records = Vec::new();
+    for (i, col) in data.config.columns.iter().enumerate() {
+        if column.is_some_and(|c| c != i) {
+            continue;
+        }
+        let mut tasks: Vec<&Task> = data
+            .tasks
+            .iter()
+            .filter(|t| !t.is_archived() && t.status.0 == col.id)
+            .filter(|t| {
+                context
+                    .as_ref()
+                    .is_none_or(|c| t.context.eq_ignore_ascii_case(c))
+            })
+            .filter(|t| {
+                [&filter, &view]
+                    .into_iter()
+                    .flatten()
+                    .all(|q| q.matches(t, &data.config, now))
+            })
+            .collect();
+        col.sort.sort(&mut tasks);
+        for task in tasks {
+            records.push(TaskRecord::new(task, col));
+        }
+    }
+    write_stdout(&output::render(&records, format)?)
+}
+
+/// Writes listing output, treating a closed pipe (`myeon list | head`) as success.
+fn write_stdout(text: &str) -> Result<(), AppError> {
+    let mut stdout = io::stdout().lock();
+    match stdout
+        .write_all(text.as_bytes())
+        .and_then(|_| stdout.flush())
+    {
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        result => Ok(result?),
+    }
+}
+
+pub fn move_task(path: &Path, reference: &str, column: &str, force: bool) -> Result<(), AppError> {
+    let (title, column_name) = MyeonData::update(path, |data| {
+        let index = data.find_task(reference)?;
+        let target = find_column(&data.config, column)?;
+        let target_column = &data.config.columns[target];
+        if data.tasks[index].status.0 != target_column.id
+            && data.config.enforce_wip_limits
+            && data.config.is_full(target, &data.tasks)
+            && !force
+        {
+            return Err(AppError::Board(format!(
+                "{} is at its limit of {}; use --force to move it anyway",
+                target_column.name,
+                target_column.wip_limit.unwrap_or_default()
+            )));
+        }
+        let column_name = target_column.name.clone();
+        let status = target_column.status();
+        if data.tasks[index].status != status {
+            data.tasks[index].rank = data::bottom_rank(&data.tasks, &status);
+        }
+        data.tasks[index].move_to(status, &data.config);
+        Ok((data.tasks[index].title.clone(), column_name))
+    })?;
+    colours::success(&format!("Moved '{}' to {}.", title, column_name));
+    Ok(())
+}
+
+pub fn edit(path: &Path, reference: &str, edit: TaskEdit) -> Result<(), AppError> {
+    let title = MyeonData::update(path, |data| {
+        let index = data.find_task(reference)?;
+        data.tasks[index].apply(edit);
+        Ok(data.tasks[index].title.clone())
+    })?;
+    colours::success(&format!("Updated '{}'.", title));
+    Ok(())
+}
+
+pub fn remove(path: &Path, reference: &str) -> Result<(), AppError> {
+    let title = MyeonData::update(path, |data| {
+        let index = data.find_task(reference)?;
+        Ok(data.trash_task(index).task.title.clone())
+    })?;
+    colours::success(&format!(
+        "Moved '{}' to the trash. `myeon trash restore` brings it back.",
+        title
+    ));
+    Ok(())
+}
+
+pub fn stats(path: &Path, context: Option<String>, weeks: usize) -> Result<(), AppError> {
+    let data = MyeonData::load_read_only(path)?;
+    let stats = stats::compute(
+        &data.config,
+        &data.tasks,
+        context.as_deref(),
+        weeks,
+        chrono::Utc::now(),
+    );
+    colours::detect_for_stdout();
+    let mut out = String::new();
+
+    out.push_str(&format!("{}\n", colours::heading("Finished per week")));
+    let widest = stats.throughput.iter().map(|(_, n)| *n).max().unwrap_or(0);
+    for (week, count) in &stats.throughput {
+        let bar = "█".repeat((count * 30).checked_div(widest).unwrap_or(0));
+        out.push_str(&format!(
+            "  {}  {:>3} {}\n",
+            week.format("%Y-%m-%d"),
+            count,
+            bar
+        ));
+    }
+
+    out.push('\n');
+    for (label, summary) in [
+        ("Lead time ", &stats.lead_time),
+        ("Cycle time", &stats.cycle_time),
+    ] {
+        let line = match summary {
+            Some(s) => format!(
+                "average {}, median {} over {} tasks",
+                stats::format_duration(s.average),
+                stats::format_duration(s.median),
+                s.count
+            ),
+            None => colours::muted("no finished tasks yet"),
+        };
+        out.push_str(&format!("{}  {}\n", colours::heading(label), line));
+    }
+
+    out.push_str(&format!("\n{}\n", colours::heading("Work in progress")));
+    for column in &stats.wip {
+        let count = match column.limit {
+            Some(limit) if column.count > limit => {
+                colours::notice(&format!("{}/{}", column.count, limit))
+            }
+            Some(limit) => format!("{}/{}", column.count, limit),
+            None => column.count.to_string(),
+        };
+        out.push_str(&format!("  {:<20} {}\n", column.name, count));
+    }
+
+    out.push_str(&format!("\n{}\n", colours::heading("Ageing in progress")));
+    if stats.ageing.is_empty() {
+        out.push_str(&format!("  {}\n", colours::muted("nothing in progress")));
+    }
+    for item in &stats.ageing {
+        out.push_str(&format!(
+            "  {:>4}  {:<12} {:>6}  {}\n",
+            item.number,
+            item.column,
+            stats::format_duration(item.age),
+            item.title
+        ));
+    }
+    write_stdout(&out)
+}
+
+pub fn cfd(
+    path: &Path,
+    context: Option<String>,
+    weeks: usize,
+    format: FlowFormat,
+) -> Result<(), AppError> {
+    let data = MyeonData::load_read_only(path)?;
+    let flow = stats::cumulative_flow(
+        &data.config,
+        &data.tasks,
+        &data.trash,
+        context.as_deref(),
+        weeks,
+        chrono::Utc::now(),
+    );
+    write_stdout(&output::render_flow(&flow, format))
+}
+
+pub fn archive(path: &Path, action: ArchiveCommand) -> Result<(), AppError> {
+    match action {
+        ArchiveCommand::List { search, format } => {
+            let data = MyeonData::load_read_only(path)?;
+            let mut tasks: Vec<&Task> = data
+                .tasks
+                .iter()
+                .filter(|t| t.is_archived())
+                .filter(|t| search.as_ref().is_none_or(|q| t.matches_text(q)))
+                .collect();
+            tasks.sort_by_key(|t| std::cmp::Reverse(t.archived_at));
+            let records: Vec<TaskRecord> = tasks
+                .iter()
+                .filter_map(|task| {
+                    let index = data.config.column_index(&task.status)?;
+                    Some(TaskRecord::new(task, &data.config.columns[index]))
+                })
+                .collect();
+            write_stdout(&output::render(&records, format)?)?;
+        }
+        ArchiveCommand::Task { task } => {
+            let title = MyeonData::update(path, |data| {
+                let index = data.find_task(&task)?;
+                let task = &mut data.tasks[index];
+                if task.is_archived() {
+                    return Err(AppError::Board(format!(
+                        "'{}' is already archived",
+                        task.title
+                    )));
+                }
+                task.archive();
+                Ok(task.title.clone())
+            })?;
+            colours::success(&format!("Archived '{}'.", title));
+        }
+        ArchiveCommand::Done => {
+            let count = MyeonData::update(path, |data| {
+                let mut count = 0;
+                for task in &mut data.tasks {
+                    if !task.is_archived() && data.config.is_done(&task.status) {
+                        task.archive();
+                        count += 1;
+                    }
+                }
+                Ok(count)
+            })?;
+            colours::success(&format!("Archived {} finished task(s).", count));
+        }
+        ArchiveCommand::Restore { task } => {
+            let title = MyeonData::update(path, |data| {
+                let index = data.find_task(&task)?;
+                let task = &mut data.tasks[index];
+                if !task.is_archived() {
+                    return Err(AppError::Board(format!("'{}' is not archived", task.title)));
+                }
+                task.unarchive();
+                Ok(task.title.clone())
+            })?;
+            colours::success(&format!("Put '{}' back on the board.", title));
+        }
+        ArchiveCommand::After { days } => {
+            MyeonData::update(path, |data| {
+                data.config.archive_after_days = days;
+                Ok(())
+            })?;
+            match days {
+                0 => colours::success("Finished tasks are no longer archived automatically."),
+                days => colours::success(&format!(
+                    "Tasks are now archived after {} days in the last column.",
+                    days
+                )),
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn trash(path: &Path, action: TrashCommand) -> Result<(), AppError> {
+    match action {
+        TrashCommand::List => {
+            let data = MyeonData::load_read_only(path)?;
+            if data.trash.is_empty() {
+                colours::info("The trash is empty.");
+                return Ok(());
+            }
+            for trashed in &data.trash {
+                let deleted = trashed
+                    .deleted_at
+                    .with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M");
+                println!(
+                    "{:>4}  {}  {}",
+                    display_ref(&trashed.task),
+                    deleted,
+                    trashed.task.title
+                );
+            }
+            match data.config.trash_days {
+                0 => println!("\nDeleted tasks are kept until the trash is emptied."),
+                days => println!("\nDeleted tasks are purged after {} days.", days),
+            }
+        }
+        TrashCommand::Restore { task } => {
+            let title = MyeonData::update(path, |data| {
+                let index = data.find_trashed(&task)?;
+                Ok(data.restore_trashed(index).title.clone())
+            })?;
+            colours::success(&format!("Restored '{}'.", title));
+        }
+        TrashCommand::Empty { yes } => {
+            if !yes {
+                colours::warn(
+                    "This deletes every task in the trash for good. Run again with --yes to confirm.",
+                );
+                return Ok(());
+            }
+            let count = MyeonData::update(path, |data| Ok(data.trash.drain(..).count()))?;
+            colours::success(&format!("Deleted {} task(s) for good.", count));
+        }
+        TrashCommand::Keep { days } => {
+            MyeonData::update(path, |data| {
+                data.config.trash_days = days;
+                Ok(())
+            })?;
+            match days {
+                0 => colours::success("Deleted tasks are now kept until the trash is emptied."),
+                days => colours::success(&format!(
+                    "Deleted tasks are now purged after {} days.",
+                    days
+                )),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// How a task is shown on the command line, and can be typed back.
+fn display_ref(task: &Task) -> String {
+    task.number.to_string()
+}
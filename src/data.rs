@@ -1,85 +1,1431 @@
+use crate::boards::BoardRegistry;
+use crate::error::AppError;
+use crate::storage;
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::fs;
-use std::path::PathBuf;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
+/// The column a task sits in, stored as the column's `id`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(transparent)]
+pub struct TaskStatus(pub String);
+
+impl TaskStatus {
+    pub fn new(column_id: impl Into<String>) -> Self {
+        Self(column_id.into())
+    }
+}
+
+/// A column on the board. Tasks refer to it by `id`, so `name` can change freely.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Column {
+    pub id: String,
+    pub name: String,
+    /// Soft cap on work in this column. Going over it is shown, not prevented,
+    /// unless the board enforces limits.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wip_limit: Option<usize>,
+    /// How cards in this column are ordered.
+    #[serde(default, skip_serializing_if = "SortMode::is_manual")]
+    pub sort: SortMode,
+}
+
+/// Order of the cards in a column.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortMode {
+    /// The order set by hand with `J`/`K`; new and newly moved cards go to the bottom.
+    /// Cards from before ranks were kept all share rank 0 and sort by priority among themselves.
+    #[default]
+    Manual,
+    /// Most important first, then most urgent.
+    Priority,
+    /// Oldest first.
+    Created,
+    /// Most recently changed first.
+    Updated,
+    /// Alphabetically, ignoring case.
+    Title,
+    /// Longest in the column first.
+    TimeInColumn,
+}
+
+impl SortMode {
+    /// Every mode, in the order `o` cycles through them.
+    pub const ALL: [SortMode; 6] = [
+        SortMode::Manual,
+        SortMode::Priority,
+        SortMode::Created,
+        SortMode::Updated,
+        SortMode::Title,
+        SortMode::TimeInColumn,
+    ];
+
+    pub fn is_manual(&self) -> bool {
+        *self == SortMode::Manual
+    }
+
+    /// The name used on the command line and in the board file.
+    pub fn name(&self) -> &'static str {
+        match self {
+            SortMode::Manual => "manual",
+            SortMode::Priority => "priority",
+            SortMode::Created => "created",
+            SortMode::Updated => "updated",
+            SortMode::Title => "title",
+            SortMode::TimeInColumn => "time-in-column",
+        }
+    }
+
+    /// How the mode reads in a sentence, e.g. "sorted by hand".
+    pub fn describe(&self) -> &'static str {
+        match self {
+            SortMode::Manual => "by hand",
+            SortMode::Priority => "by priority",
+            SortMode::Created => "by creation date",
+            SortMode::Updated => "by last update",
+            SortMode::Title => "by title",
+            SortMode::TimeInColumn => "by time in column",
+        }
+    }
+
+    pub fn next(&self) -> SortMode {
+        let index = SortMode::ALL.iter().position(|m| m == self).unwrap_or(0);
+        SortMode::ALL[(index + 1) % SortMode::ALL.len()]
+    }
+
+    /// Sorts `tasks`, using the hand-placed rank to break ties.
+    pub fn sort(&self, tasks: &mut [&Task]) {
+        match self {
+            SortMode::Manual => tasks.sort_by_key(|t| (t.rank, t.importance(), t.created_at)),
+            SortMode::Priority => tasks.sort_by_key(|t| (t.importance(), t.rank)),
+            SortMode::Created => tasks.sort_by_key(|t| (t.created_at, t.rank)),
+            SortMode::Updated => tasks.sort_by_key(|t| {
+                (
+                    std::cmp::Reverse(t.updated_at.unwrap_or(t.created_at)),
+                    t.rank,
+                )
+            }),
+            SortMode::Title => tasks.sort_by_cached_key(|t| (t.title.to_lowercase(), t.rank)),
+            SortMode::TimeInColumn => tasks.sort_by_key(|t| (t.entered_column_at(), t.rank)),
+        }
+    }
+}
+
+impl std::str::FromStr for SortMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_ascii_lowercase();
+        match s.as_str() {
+            "hand" => Ok(SortMode::Manual),
+            "age" => Ok(SortMode::TimeInColumn),
+            _ => SortMode::ALL
+                .into_iter()
+                .find(|m| m.name() == s)
+                .ok_or_else(|| {
+                    format!(
+                        "unknown sort '{}' (use {})",
+                        s,
+                        SortMode::ALL.map(|m| m.name()).join(", ")
+                    )
+                }),
+        }
+    }
+}
+
+impl Column {
+    pub fn new(id: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            wip_limit: None,
+            sort: SortMode::default(),
+        }
+    }
+
+    pub fn with_wip_limit(mut self, limit: usize) -> Self {
+        self.wip_limit = Some(limit);
+        self
+    }
+
+    pub fn status(&self) -> TaskStatus {
+        TaskStatus::new(self.id.clone())
+    }
+}
+
+/// Board-level settings, stored next to the tasks in the board file.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
-pub enum TaskStatus {
-    Idea,
-    Todo,
-    Doing,
-    Done,
+pub struct BoardConfig {
+    /// Columns in board order. Tasks move forward and backward along this list.
+    #[serde(default = "default_columns")]
+    pub columns: Vec<Column>,
+    /// Ask for confirmation before moving a task into a column at its WIP limit.
+    #[serde(default)]
+    pub enforce_wip_limits: bool,
+    /// Days a deleted task stays in the trash before it is purged. 0 keeps it forever.
+    #[serde(default = "default_trash_days")]
+    pub trash_days: u32,
+    /// Days a task stays in the last column before it is archived. 0 turns auto-archiving off.
+    #[serde(default)]
+    pub archive_after_days: u32,
+    /// Days in the same column after which a card shows a quiet age cue, one dot per
+    /// threshold passed. Empty turns the cue off.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ageing_days: Vec<u32>,
+    /// Saved filters, picked with `V` in the board or `--view` on the command line.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub views: Vec<View>,
+    /// Id of the column where work counts as started, for cycle time and ageing.
+    /// `None` means the column before the last.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_column: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct ColumnConfig {
-    pub idea_label: String,
-    pub todo_label: String,
-    pub doing_label: String,
-    pub done_label: String,
+/// A named filter expression, see `query::Query`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct View {
+    pub name: String,
+    pub query: String,
 }
 
-impl Default for ColumnConfig {
+impl Default for BoardConfig {
     fn default() -> Self {
         Self {
-            idea_label: "Idea".into(),
-            todo_label: "To Do".into(),
-            doing_label: "Doing".into(),
-            done_label: "Done".into(),
+            columns: default_columns(),
+            enforce_wip_limits: false,
+            trash_days: default_trash_days(),
+            archive_after_days: 0,
+            ageing_days: Vec::new(),
+            views: Vec::new(),
+            start_column: None,
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+fn default_trash_days() -> u32 {
+    30
+}
+
+/// The original four columns. Their ids match the statuses used before columns
+/// were configurable, so older files need no task rewriting.
+fn default_columns() -> Vec<Column> {
+    vec![
+        Column::new("Idea", "Ideas"),
+        Column::new("Todo", "To Do"),
+        Column::new("Doing", "Doing").with_wip_limit(3),
+        Column::new("Done", "Done"),
+    ]
+}
+
+impl BoardConfig {
+    pub fn column_index(&self, status: &TaskStatus) -> Option<usize> {
+        self.columns.iter().position(|c| c.id == status.0)
+    }
+
+    /// Indices of the columns where work is in progress: from the start column
+    /// up to, but not including, the last.
+    pub fn in_progress(&self) -> std::ops::Range<usize> {
+        let last = self.columns.len().saturating_sub(1);
+        let start = self
+            .start_column
+            .as_ref()
+            .and_then(|id| self.columns.iter().position(|c| &c.id == id))
+            .unwrap_or(last.saturating_sub(1));
+        start.min(last)..last
+    }
+
+    /// Finds a saved view by name, ignoring case.
+    pub fn find_view(&self, name: &str) -> Option<&View> {
+        self.views
+            .iter()
+            .find(|v| v.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Finds a column by id or name, ignoring case.
+    pub fn find_column(&self, reference: &str) -> Option<usize> {
+        self.columns.iter().position(|c| {
+            c.id.eq_ignore_ascii_case(reference) || c.name.eq_ignore_ascii_case(reference)
+        })
+    }
+
+    /// True when moving one more task into column `index` would go over its limit.
+    pub fn is_full(&self, index: usize, tasks: &[Task]) -> bool {
+        let Some(column) = self.columns.get(index) else {
+            return false;
+        };
+        column.wip_limit.is_some_and(|limit| {
+            tasks
+                .iter()
+                .filter(|t| !t.is_archived() && t.status.0 == column.id)
+                .count()
+                >= limit
+        })
+    }
+
+    /// Sort mode of the column `status` refers to.
+    pub fn sort_mode(&self, status: &TaskStatus) -> SortMode {
+        self.column_index(status)
+            .map_or(SortMode::default(), |i| self.columns[i].sort)
+    }
+
+    /// True for the last column, where finished work ends up.
+    pub fn is_done(&self, status: &TaskStatus) -> bool {
+        self.columns.last().is_some_and(|c| c.id == status.0)
+    }
+
+    /// Where new tasks land.
+    pub fn first_status(&self) -> TaskStatus {
+        self.columns[0].status()
+    }
+
+    /// Adds a column named `name` at `position` (or the end), giving it an id
+    /// derived from the name that no other column uses.
+    pub fn add_column(&mut self, name: &str, position: Option<usize>) -> &Column {
+        let mut id = name.to_string();
+        let mut n = 2;
+        while self.columns.iter().any(|c| c.id == id) {
+            id = format!("{}-{}", name, n);
+            n += 1;
+        }
+        let index = position
+            .unwrap_or(self.columns.len())
+            .min(self.columns.len());
+        self.columns.insert(index, Column::new(id, name));
+        &self.columns[index]
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub enum Priority {
     High,   // Will use ACCENT_URGENT (MutedRed)
     Medium, // Will use QuietAmber
     Low,    // Will use MutedDetail
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+impl std::str::FromStr for Priority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "high" | "h" | "3" => Ok(Priority::High),
+            "medium" | "med" | "m" | "2" => Ok(Priority::Medium),
+            "low" | "l" | "1" => Ok(Priority::Low),
+            _ => Err(format!(
+                "unknown priority '{}' (use high, medium or low)",
+                s
+            )),
+        }
+    }
+}
+
+/// How soon a task needs attention, independent of how much it matters.
+/// Together with `Priority` it places a task in the Eisenhower matrix.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub enum Urgency {
+    High,
+    Medium,
+    #[default]
+    Low,
+}
+
+impl std::str::FromStr for Urgency {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "high" | "h" | "3" => Ok(Urgency::High),
+            "medium" | "med" | "m" | "2" => Ok(Urgency::Medium),
+            "low" | "l" | "1" => Ok(Urgency::Low),
+            _ => Err(format!("unknown urgency '{}' (use high, medium or low)", s)),
+        }
+    }
+}
+
+/// Context given to tasks that were not put in one.
+pub const DEFAULT_CONTEXT: &str = "General";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Task {
     pub id: uuid::Uuid,
+    /// Short, board-unique number for typing (`#12`). Assigned when the task is
+    /// first saved; 0 until then.
+    #[serde(default)]
+    pub number: u64,
     pub title: String,
+    #[serde(default)]
     pub description: Option<String>,
     pub status: TaskStatus,
     pub priority: Priority,
+    #[serde(default)]
+    pub urgency: Urgency,
+    /// Position within its column when sorted by hand; lower comes first.
+    /// Cards that were never placed by hand share 0.
+    #[serde(default)]
+    pub rank: i64,
     pub context: String, // e.g., "Work", "Personal"
     pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Last time anything about the task changed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// When the task last reached the last column. Cleared when it moves back.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Set while the task is archived: kept, but off the board.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archived_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// When the task was last taken out of the archive. Auto-archiving counts from here
+    /// rather than from `completed_at`, so a restored task is not archived straight away.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unarchived_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Every move between columns, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transitions: Vec<Transition>,
+}
+
+/// One move of a task from column `from` to column `to`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Transition {
+    pub from: TaskStatus,
+    pub to: TaskStatus,
+    pub at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Changes to apply to a task. `None` leaves a field as it is.
+#[derive(Default)]
+pub struct TaskEdit {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub context: Option<String>,
+    pub priority: Option<Priority>,
+    pub urgency: Option<Urgency>,
+}
+
+impl Task {
+    pub fn new(
+        title: impl Into<String>,
+        status: TaskStatus,
+        priority: Priority,
+        context: Option<String>,
+        description: Option<String>,
+    ) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4(),
+            number: 0,
+            title: title.into(),
+            description: description.filter(|d| !d.is_empty()),
+            status,
+            priority,
+            urgency: Urgency::default(),
+            rank: 0,
+            context: context
+                .filter(|c| !c.is_empty())
+                .unwrap_or_else(|| DEFAULT_CONTEXT.to_string()),
+            created_at: chrono::Utc::now(),
+            updated_at: None,
+            completed_at: None,
+            archived_at: None,
+            unarchived_at: None,
+            transitions: Vec::new(),
+        }
+    }
+
+    /// The column the task was in at `time`, or `None` if it did not exist yet.
+    /// Tasks never moved have been in their current column all along.
+    pub fn status_at(&self, time: chrono::DateTime<chrono::Utc>) -> Option<&TaskStatus> {
+        if self.created_at > time {
+            return None;
+        }
+        match self.transitions.iter().rev().find(|t| t.at <= time) {
+            Some(transition) => Some(&transition.to),
+            None => Some(self.transitions.first().map_or(&self.status, |t| &t.from)),
+        }
+    }
+
+    /// Sort key putting the most important tasks first, urgency breaking ties.
+    pub fn importance(&self) -> (u8, u8) {
+        let priority = match self.priority {
+            Priority::High => 0,
+            Priority::Medium => 1,
+            Priority::Low => 2,
+        };
+        let urgency = match self.urgency {
+            Urgency::High => 0,
+            Urgency::Medium => 1,
+            Urgency::Low => 2,
+        };
+        (priority, urgency)
+    }
+
+    /// When the task arrived in the column it is in now.
+    pub fn entered_column_at(&self) -> chrono::DateTime<chrono::Utc> {
+        self.transitions
+            .iter()
+            .rev()
+            .find(|t| t.to == self.status)
+            .map_or(self.created_at, |t| t.at)
+    }
+
+    pub fn is_archived(&self) -> bool {
+        self.archived_at.is_some()
+    }
+
+    /// Moves the task to `status`, recording the transition and keeping
+    /// `completed_at` in step with the last column.
+    pub fn move_to(&mut self, status: TaskStatus, config: &BoardConfig) {
+        if status == self.status {
+            return;
+        }
+        let now = chrono::Utc::now();
+        self.transitions.push(Transition {
+            from: self.status.clone(),
+            to: status.clone(),
+            at: now,
+        });
+        self.completed_at = config.is_done(&status).then_some(now);
+        self.status = status;
+        self.updated_at = Some(now);
+    }
+
+    pub fn archive(&mut self) {
+        let now = chrono::Utc::now();
+        self.archived_at = Some(now);
+        self.updated_at = Some(now);
+    }
+
+    /// Puts an archived task back on the board. When it finished stays as it was.
+    pub fn unarchive(&mut self) {
+        let now = chrono::Utc::now();
+        self.archived_at = None;
+        self.unarchived_at = Some(now);
+        self.updated_at = Some(now);
+    }
+
+    /// Whether `query` appears in the title, description or context, ignoring case.
+    pub fn matches_text(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        self.title.to_lowercase().contains(&query)
+            || self.context.to_lowercase().contains(&query)
+            || self
+                .description
+                .as_ref()
+                .is_some_and(|d| d.to_lowercase().contains(&query))
+    }
+
+    /// True if every word of `query` fuzzily matches the title or the description:
+    /// its letters appear there in order, ignoring case, though not necessarily
+    /// next to each other.
+    pub fn matches_fuzzy(&self, query: &str) -> bool {
+        query.split_whitespace().all(|word| {
+            is_subsequence(word, &self.title)
+                || self
+                    .description
+                    .as_ref()
+                    .is_some_and(|d| is_subsequence(word, d))
+        })
+    }
+
+    /// Applies an edit. Empty strings count as "unchanged", like an untouched form field.
+    pub fn apply(&mut self, edit: TaskEdit) {
+        let before = self.clone();
+        if let Some(title) = edit.title.filter(|t| !t.is_empty()) {
+            self.title = title;
+        }
+        if let Some(description) = edit.description.filter(|d| !d.is_empty()) {
+            self.description = Some(description);
+        }
+        if let Some(context) = edit.context.filter(|c| !c.is_empty()) {
+            self.context = context;
+        }
+        if let Some(priority) = edit.priority {
+            self.priority = priority;
+        }
+        if let Some(urgency) = edit.urgency {
+            self.urgency = urgency;
+        }
+        if *self != before {
+            self.updated_at = Some(chrono::Utc::now());
+        }
+    }
+}
+
+/// Space left between hand-placed ranks, so a card can usually move by changing only its own rank.
+pub const RANK_GAP: i64 = 1024;
+
+/// A rank that puts a card at the bottom of the column `status`.
+pub fn bottom_rank(tasks: &[Task], status: &TaskStatus) -> i64 {
+    tasks
+        .iter()
+        .filter(|t| !t.is_archived() && &t.status == status)
+        .map(|t| t.rank)
+        .max()
+        .map_or(0, |highest| highest + RANK_GAP)
+}
+
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut haystack = haystack.chars().flat_map(char::to_lowercase);
+    needle
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|c| haystack.any(|h| h == c))
+}
+
+/// A deleted task, kept until it is restored or purged.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TrashedTask {
+    #[serde(flatten)]
+    pub task: Task,
+    pub deleted_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl TrashedTask {
+    pub fn new(task: Task) -> Self {
+        Self {
+            task,
+            deleted_at: chrono::Utc::now(),
+        }
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+/// Name of a per-project board, found by walking up from the current directory.
+pub const PROJECT_BOARD_FILE: &str = ".myeon.json";
+
+/// Version of the board file format this build reads and writes.
+/// Bump it together with a new entry in `MIGRATIONS`.
+pub const SCHEMA_VERSION: u64 = 2;
+
+/// Upgrades a document from version `i` to `i + 1`, indexed by the version it starts from.
+/// Files written before versioning existed are version 0.
+const MIGRATIONS: &[fn(&mut Value)] = &[migrate_v0_to_v1, migrate_v1_to_v2];
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct MyeonData {
+    pub schema_version: u64,
+    /// Bumped on every save, so an instance can tell the file changed under it.
+    #[serde(default)]
+    pub revision: u64,
+    /// Number the next new task gets. Only ever grows, so numbers are never reused.
+    #[serde(default)]
+    pub next_task_number: u64,
+    #[serde(flatten)]
+    pub config: BoardConfig,
     pub tasks: Vec<Task>,
+    /// Deleted tasks, newest last.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trash: Vec<TrashedTask>,
+}
+
+impl Default for MyeonData {
+    fn default() -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            revision: 0,
+            next_task_number: 1,
+            config: BoardConfig::default(),
+            tasks: vec![],
+            trash: vec![],
+        }
+    }
+}
+
+/// Result of a synced save: the board as written, and what had to be merged.
+pub struct SyncOutcome {
+    pub data: MyeonData,
+    pub merged: bool,
+    pub conflicts: usize,
 }
 
 impl MyeonData {
+    /// Works out which board file to use, in order of precedence:
+    /// an explicit `--board` path or board name (or `MYEON_BOARD`), the nearest `.myeon.json`
+    /// in the current directory or its parents, then the user-level board.
+    pub fn resolve_path(explicit: Option<&Path>) -> Result<PathBuf, AppError> {
+        if let Some(path) = explicit {
+            // `--board roadmap` opens the named board, unless a file by that name exists here.
+            if !path.exists()
+                && let Some(name) = path.to_str()
+                && let Some(board) = BoardRegistry::open().ok().and_then(|r| r.find(name))
+            {
+                return Ok(board.path);
+            }
+            return Ok(path.to_path_buf());
+        }
+        if let Ok(cwd) = std::env::current_dir()
+            && let Some(path) = cwd
+                .ancestors()
+                .map(|dir| dir.join(PROJECT_BOARD_FILE))
+                .find(|path| path.is_file())
+        {
+            return Ok(path);
+        }
+        Self::user_board_path().ok_or(AppError::NoBoardPath)
+    }
+
     /// Gets the platform-specific config directory:
     /// e.g., ~/.config/myeon/tasks.json on Linux
-    fn get_data_path() -> PathBuf {
-        let proj_dirs = ProjectDirs::from("com", "ilseon", "myeon")
-            .expect("Could not determine config directory");
+    pub fn user_board_path() -> Option<PathBuf> {
+        let proj_dirs = ProjectDirs::from("com", "ilseon", "myeon")?;
+        Some(proj_dirs.config_dir().join("tasks.json"))
+    }
+
+    /// Modification time of the board file, used to notice outside changes.
+    pub fn modified(path: &Path) -> Option<SystemTime> {
+        fs::metadata(path).and_then(|m| m.modified()).ok()
+    }
 
-        let mut path = proj_dirs.config_dir().to_path_buf();
-        // Ensure directory exists
-        let _ = fs::create_dir_all(&path);
-        path.push("tasks.json");
-        path
+    /// Loads the board at `path` to change it.
+    /// A missing file is an empty board; an unparsable one is quarantined
+    /// and reported so it never gets overwritten by a fresh board.
+    pub fn load(path: &Path) -> Result<Self, AppError> {
+        // A file from a newer myeon is fine, just not for us: refuse without quarantining it.
+        Self::load_read_only(path).map_err(|e| match e {
+            AppError::SerdeJson(source) => match Self::quarantine(path) {
+                Ok(quarantined) => AppError::CorruptData {
+                    path: quarantined,
+                    source,
+                },
+                Err(_) => AppError::SerdeJson(source),
+            },
+            other => other,
+        })
     }
 
-    pub fn load() -> Self {
-        let path = Self::get_data_path();
-        if let Ok(content) = fs::read_to_string(path) {
-            serde_json::from_str(&content).unwrap_or(MyeonData { tasks: vec![] })
+    /// Loads the board at `path` without touching the file, for commands that only show it.
+    /// A missing file is an empty board, unless an earlier load quarantined it and
+    /// nobody has restored it or started a new board since.
+    pub fn load_read_only(path: &Path) -> Result<Self, AppError> {
+        match fs::read_to_string(path) {
+            Ok(content) => Self::parse(&content),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => match Self::quarantined(path) {
+                Some(quarantined) => Err(AppError::Quarantined { path: quarantined }),
+                None => Ok(Self::default()),
+            },
+            Err(e) => Err(AppError::FileReadError(format!(
+                "{}: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+
+    /// Parses a board document, upgrading older schema versions step by step.
+    fn parse(content: &str) -> Result<Self, AppError> {
+        let mut doc: Value = serde_json::from_str(content)?;
+        let version = doc
+            .get("schema_version")
+            .and_then(Value::as_u64)
+            .unwrap_or(0);
+        if version > SCHEMA_VERSION {
+            return Err(AppError::UnsupportedSchema {
+                found: version,
+                supported: SCHEMA_VERSION,
+            });
+        }
+
+        for migrate in &MIGRATIONS[version as usize..] {
+            migrate(&mut doc);
+        }
+        if let Some(obj) = doc.as_object_mut() {
+            obj.insert("schema_version".into(), SCHEMA_VERSION.into());
+        }
+        let mut data: Self = serde_json::from_value(doc)?;
+        data.normalize();
+        data.assign_numbers();
+        Ok(data)
+    }
+
+    /// Keeps a hand-edited board usable: there is always at least one column,
+    /// and tasks pointing at a column that no longer exists land in the first one,
+    /// or in the last one if they are archived, so they still count as finished work.
+    /// Also drops trash entries that are back on the board or past `trash_days`,
+    /// and archives finished tasks past `archive_after_days`.
+    fn normalize(&mut self) {
+        let tasks = &self.tasks;
+        self.trash
+            .retain(|t| !tasks.iter().any(|task| task.id == t.task.id));
+        if self.config.trash_days > 0 {
+            let cutoff = chrono::Utc::now() - chrono::Duration::days(self.config.trash_days.into());
+            self.trash.retain(|t| t.deleted_at > cutoff);
+        }
+
+        if self.config.columns.is_empty() {
+            self.config.columns = default_columns();
+        }
+        let first = self.config.first_status();
+        let last = self.config.columns[self.config.columns.len() - 1].status();
+        for task in &mut self.tasks {
+            if self.config.column_index(&task.status).is_none() {
+                task.status = if task.is_archived() {
+                    last.clone()
+                } else {
+                    first.clone()
+                };
+            }
+        }
+        let columns = &self.config.columns;
+        if !self
+            .config
+            .start_column
+            .as_ref()
+            .is_none_or(|id| columns.iter().any(|c| &c.id == id))
+        {
+            self.config.start_column = None;
+        }
+        self.auto_archive();
+    }
+
+    /// Archives tasks that have sat in the last column for `archive_after_days`.
+    /// The archive time is derived from when they finished or were last taken out
+    /// of the archive, so every reader of the file comes to the same result.
+    fn auto_archive(&mut self) {
+        if self.config.archive_after_days == 0 {
+            return;
+        }
+        let after = chrono::Duration::days(self.config.archive_after_days.into());
+        let now = chrono::Utc::now();
+        for task in &mut self.tasks {
+            if task.is_archived() || !self.config.is_done(&task.status) {
+                continue;
+            }
+            // Tasks finished before completion times were recorded count from their creation.
+            let since = task
+                .completed_at
+                .unwrap_or(task.created_at)
+                .max(task.unarchived_at.unwrap_or_default());
+            let archive_at = since + after;
+            if archive_at <= now {
+                task.archived_at = Some(archive_at);
+            }
+        }
+    }
+
+    /// Adds a task and numbers it straight away. Only for boards held under the
+    /// save lock, like inside `update`.
+    pub fn add_task(&mut self, task: Task) -> &Task {
+        self.tasks.push(task);
+        self.assign_numbers();
+        self.tasks.last().expect("task was just pushed")
+    }
+
+    /// Moves the task at `index` to the trash.
+    pub fn trash_task(&mut self, index: usize) -> &TrashedTask {
+        let task = self.tasks.remove(index);
+        self.trash.push(TrashedTask::new(task));
+        self.trash.last().expect("task was just trashed")
+    }
+
+    /// Puts the trashed task at `index` back on the board, in the column it was deleted from.
+    /// An entry for a task that is already on the board is just dropped.
+    pub fn restore_trashed(&mut self, index: usize) -> &Task {
+        let trashed = self.trash.remove(index);
+        let position = match self.tasks.iter().position(|t| t.id == trashed.task.id) {
+            Some(position) => position,
+            None => {
+                self.tasks.push(trashed.task);
+                self.tasks.len() - 1
+            }
+        };
+        &self.tasks[position]
+    }
+
+    /// Gives every task without a number the next free one, oldest first.
+    /// Done only while reading or under the save lock, so two instances never hand out the same number.
+    fn assign_numbers(&mut self) {
+        let highest = self.tasks.iter().map(|t| t.number).max().unwrap_or(0);
+        self.next_task_number = self.next_task_number.max(highest + 1);
+
+        let mut unnumbered: Vec<&mut Task> =
+            self.tasks.iter_mut().filter(|t| t.number == 0).collect();
+        unnumbered.sort_by_key(|t| (t.created_at, t.id));
+        for task in unnumbered {
+            task.number = self.next_task_number;
+            self.next_task_number += 1;
+        }
+    }
+
+    /// Moves a broken data file aside as `tasks.json.corrupt-<timestamp>`.
+    fn quarantine(path: &Path) -> io::Result<PathBuf> {
+        let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ");
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(format!(".corrupt-{}", stamp));
+        let target = path.with_file_name(name);
+        fs::rename(path, &target)?;
+        Ok(target)
+    }
+
+    /// The most recent quarantined copy of the board at `path`, if any.
+    fn quarantined(path: &Path) -> Option<PathBuf> {
+        let mut prefix = path.file_name()?.to_os_string();
+        prefix.push(".corrupt-");
+        let prefix = prefix.to_string_lossy().into_owned();
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        fs::read_dir(dir)
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+            .map(|entry| entry.path())
+            .max()
+    }
+
+    /// Re-reads a quarantined file (typically after the user fixed it by hand)
+    /// and, if it parses, moves it back into place as the live board.
+    /// Refuses if a board has appeared at `path` in the meantime.
+    pub fn restore_quarantined(quarantined: &Path, path: &Path) -> Result<Self, AppError> {
+        let data = Self::read(quarantined)?;
+        let _lock = storage::lock(path)?;
+        if path.exists() {
+            return Err(AppError::Board(format!(
+                "{} exists again; move it away before putting back {}",
+                path.display(),
+                quarantined.display()
+            )));
+        }
+        fs::rename(quarantined, path)?;
+        Ok(data)
+    }
+
+    /// Parses a board file without any of the quarantine handling of `load`.
+    /// Used for files that are not the live board, like backups.
+    pub fn read(path: &Path) -> Result<Self, AppError> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| AppError::FileReadError(format!("{}: {}", path.display(), e)))?;
+        Self::parse(&content)
+    }
+
+    /// Saves the board under the cross-process lock. If another instance saved
+    /// since `base` (the board as this instance last read or wrote it),
+    /// its changes are merged in first.
+    pub fn save_synced(mut self, path: &Path, base: &MyeonData) -> Result<SyncOutcome, AppError> {
+        let _lock = storage::lock(path)?;
+
+        // A missing file means there is nothing to merge with, not that every task was deleted.
+        let on_disk = if path.exists() {
+            Some(Self::load(path)?)
+        } else if let Some(quarantined) = Self::quarantined(path) {
+            return Err(AppError::Quarantined { path: quarantined });
         } else {
-            MyeonData { tasks: vec![] }
+            None
+        };
+        let mut merged = false;
+        let mut conflicts = 0;
+        let mut disk_revision = 0;
+        if let Some(on_disk) = on_disk {
+            disk_revision = on_disk.revision;
+            self.next_task_number = self.next_task_number.max(on_disk.next_task_number);
+            // Scripts may rewrite the file without bumping the revision, so compare contents too.
+            if on_disk.revision != base.revision || !on_disk.same_content(base) {
+                conflicts = merge(base, &mut self, &on_disk);
+                merged = true;
+            }
         }
+
+        self.assign_numbers();
+        self.revision = base.revision.max(disk_revision) + 1;
+        self.save(path)?;
+        Ok(SyncOutcome {
+            data: self,
+            merged,
+            conflicts,
+        })
+    }
+
+    /// Applies `change` to the board at `path` under the lock and saves it.
+    /// For one-shot edits (like the CLI) that read and write in one go.
+    pub fn update<T>(
+        path: &Path,
+        change: impl FnOnce(&mut MyeonData) -> Result<T, AppError>,
+    ) -> Result<T, AppError> {
+        let _lock = storage::lock(path)?;
+        let mut data = Self::load(path)?;
+        let result = change(&mut data)?;
+        data.normalize();
+        data.assign_numbers();
+        data.revision += 1;
+        data.save(path)?;
+        Ok(result)
     }
 
-    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let path = Self::get_data_path();
+    /// Finds a task as typed on the command line: its number (`12` or `#12`),
+    /// or a unique prefix of its UUID.
+    pub fn find_task(&self, reference: &str) -> Result<usize, AppError> {
+        find_reference(&self.tasks, |t| t, reference)
+    }
+
+    /// Like `find_task`, but among the tasks in the trash.
+    pub fn find_trashed(&self, reference: &str) -> Result<usize, AppError> {
+        find_reference(&self.trash, |t| &t.task, reference)
+    }
+
+    pub fn same_content(&self, other: &MyeonData) -> bool {
+        self.tasks == other.tasks && self.config == other.config && self.trash == other.trash
+    }
+
+    /// Saves the board atomically, first rotating the previous version into the
+    /// backups when one is due (see `storage::backup_if_due`).
+    pub fn save(&self, path: &Path) -> Result<(), AppError> {
         let content = serde_json::to_string_pretty(self)?;
-        fs::write(path, content)?;
+        storage::backup_if_due(path)?;
+        storage::write_atomic(path, content.as_bytes())?;
         Ok(())
     }
 }
+
+/// Finds the item whose task matches `reference`, see `MyeonData::find_task`.
+fn find_reference<T>(
+    items: &[T],
+    task: impl Fn(&T) -> &Task,
+    reference: &str,
+) -> Result<usize, AppError> {
+    let reference = reference.trim().to_ascii_lowercase();
+    let number = reference.strip_prefix('#').unwrap_or(&reference);
+    if let Ok(number) = number.parse::<u64>()
+        && let Some(index) = items.iter().position(|t| task(t).number == number)
+    {
+        return Ok(index);
+    }
+    if reference.is_empty() || reference.starts_with('#') {
+        return Err(AppError::TaskNotFound(reference));
+    }
+
+    let matches: Vec<&Task> = items
+        .iter()
+        .map(&task)
+        .filter(|t| t.id.to_string().starts_with(&reference))
+        .collect();
+    match matches.as_slice() {
+        [found] => Ok(items
+            .iter()
+            .position(|t| task(t).id == found.id)
+            .expect("match came from items")),
+        [] => Err(AppError::TaskNotFound(reference)),
+        _ => Err(AppError::AmbiguousTask {
+            reference,
+            candidates: matches
+                .iter()
+                .map(|t| format!("#{} {}", t.number, t.title))
+                .collect::<Vec<_>>()
+                .join(", "),
+        }),
+    }
+}
+
+/// v0 was the unversioned `{ "tasks": [...] }` file; v1 adds `schema_version` and `revision`.
+fn migrate_v0_to_v1(doc: &mut Value) {
+    if let Some(obj) = doc.as_object_mut() {
+        obj.entry("tasks").or_insert_with(|| Value::Array(vec![]));
+        obj.entry("revision").or_insert(Value::from(0));
+    }
+}
+
+/// v2 makes the columns part of the board; older boards get the original four.
+fn migrate_v1_to_v2(doc: &mut Value) {
+    if let Some(obj) = doc.as_object_mut() {
+        obj.entry("columns")
+            .or_insert_with(|| serde_json::to_value(default_columns()).unwrap_or_default());
+    }
+}
+
+/// Merges `theirs` into `ours`, both descended from `base`, and returns the
+/// number of conflicts. Tasks merge one by one; board settings are taken from
+/// whichever side changed them, ours winning if both did.
+pub fn merge(base: &MyeonData, ours: &mut MyeonData, theirs: &MyeonData) -> usize {
+    let (tasks, mut conflicts) = merge_tasks(&base.tasks, &ours.tasks, &theirs.tasks);
+    ours.tasks = tasks;
+    ours.trash = merge_trash(&base.trash, &ours.trash, &theirs.trash);
+    ours.next_task_number = ours.next_task_number.max(theirs.next_task_number);
+    if ours.config == base.config {
+        ours.config = theirs.config.clone();
+    } else if theirs.config != base.config && theirs.config != ours.config {
+        conflicts += 1;
+    }
+    ours.normalize();
+    conflicts
+}
+
+/// Three-way merge of the trash by task id. An entry stays unless one side
+/// restored or purged it; `normalize` then drops anything back on the board.
+fn merge_trash(
+    base: &[TrashedTask],
+    ours: &[TrashedTask],
+    theirs: &[TrashedTask],
+) -> Vec<TrashedTask> {
+    let contains = |list: &[TrashedTask], id| list.iter().any(|t: &TrashedTask| t.task.id == id);
+    let mut merged: Vec<TrashedTask> = ours
+        .iter()
+        .filter(|t| contains(theirs, t.task.id) || !contains(base, t.task.id))
+        .cloned()
+        .collect();
+    merged.extend(
+        theirs
+            .iter()
+            .filter(|t| !contains(ours, t.task.id) && !contains(base, t.task.id))
+            .cloned(),
+    );
+    merged
+}
+
+/// Three-way merge of two task lists by `id`. A change made on only one side wins;
+/// when both sides changed the same task, ours wins and it counts as a conflict.
+/// An edit always beats a delete.
+pub fn merge_tasks(base: &[Task], ours: &[Task], theirs: &[Task]) -> (Vec<Task>, usize) {
+    fn find(list: &[Task], id: uuid::Uuid) -> Option<&Task> {
+        list.iter().find(|t| t.id == id)
+    }
+
+    let mut merged = Vec::with_capacity(ours.len().max(theirs.len()));
+    let mut conflicts = 0;
+
+    for our_task in ours {
+        let base_task = find(base, our_task.id);
+        match (base_task, find(theirs, our_task.id)) {
+            (_, Some(their_task)) => {
+                if base_task == Some(their_task) || their_task == our_task {
+                    merged.push(our_task.clone());
+                } else if base_task == Some(our_task) {
+                    merged.push(their_task.clone());
+                } else {
+                    conflicts += 1;
+                    merged.push(our_task.clone());
+                }
+            }
+            // Deleted elsewhere; only keep it if we changed it meanwhile.
+            (Some(base_task), None) => {
+                if base_task != our_task {
+                    conflicts += 1;
+                    merged.push(our_task.clone());
+                }
+            }
+            (None, None) => merged.push(our_task.clone()),
+        }
+    }
+
+    for their_task in theirs {
+        if find(ours, their_task.id).is_some() {
+            continue;
+        }
+        match find(base, their_task.id) {
+            None => merged.push(their_task.clone()),
+            // Deleted here; keep it only if they changed it meanwhile.
+            Some(base_task) if base_task != their_task => {
+                conflicts += 1;
+                merged.push(their_task.clone());
+            }
+            Some(_) => {}
+        }
+    }
+
+    (merged, conflicts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(title: &str) -> Task {
+        Task::new(title, TaskStatus::new("todo"), Priority::Low, None, None)
+    }
+
+    fn renamed(task: &Task, title: &str) -> Task {
+        Task {
+            title: title.to_string(),
+            ..task.clone()
+        }
+    }
+
+    #[test]
+    fn merge_keeps_edits_to_different_tasks() {
+        let (a, b) = (task("a"), task("b"));
+        let base = vec![a.clone(), b.clone()];
+        let ours = vec![renamed(&a, "a, ours"), b.clone()];
+        let theirs = vec![a.clone(), renamed(&b, "b, theirs")];
+
+        let (merged, conflicts) = merge_tasks(&base, &ours, &theirs);
+
+        assert_eq!(conflicts, 0);
+        assert_eq!(
+            merged,
+            vec![renamed(&a, "a, ours"), renamed(&b, "b, theirs")]
+        );
+    }
+
+    #[test]
+    fn merge_keeps_tasks_added_on_either_side() {
+        let (a, b, c) = (task("a"), task("b"), task("c"));
+        let base = vec![a.clone()];
+
+        let (merged, conflicts) =
+            merge_tasks(&base, &[a.clone(), b.clone()], &[a.clone(), c.clone()]);
+
+        assert_eq!(conflicts, 0);
+        assert_eq!(merged, vec![a, b, c]);
+    }
+
+    #[test]
+    fn merge_prefers_ours_when_both_edit_the_same_task() {
+        let a = task("a");
+
+        let (merged, conflicts) = merge_tasks(
+            std::slice::from_ref(&a),
+            &[renamed(&a, "ours")],
+            &[renamed(&a, "theirs")],
+        );
+
+        assert_eq!(conflicts, 1);
+        assert_eq!(merged, vec![renamed(&a, "ours")]);
+    }
+
+    #[test]
+    fn merge_same_edit_on_both_sides_is_no_conflict() {
+        let a = task("a");
+
+        let (merged, conflicts) = merge_tasks(
+            std::slice::from_ref(&a),
+            &[renamed(&a, "same")],
+            &[renamed(&a, "same")],
+        );
+
+        assert_eq!(conflicts, 0);
+        assert_eq!(merged, vec![renamed(&a, "same")]);
+    }
+
+    #[test]
+    fn merge_edit_beats_delete() {
+        let a = task("a");
+        let base = vec![a.clone()];
+
+        let (merged, conflicts) = merge_tasks(&base, &[renamed(&a, "edited")], &[]);
+        assert_eq!((merged, conflicts), (vec![renamed(&a, "edited")], 1));
+
+        let (merged, conflicts) = merge_tasks(&base, &[], &[renamed(&a, "edited")]);
+        assert_eq!((merged, conflicts), (vec![renamed(&a, "edited")], 1));
+    }
+
+    #[test]
+    fn merge_applies_an_untouched_delete() {
+        let (a, b) = (task("a"), task("b"));
+        let base = vec![a.clone(), b.clone()];
+
+        let (merged, conflicts) = merge_tasks(&base, &base, std::slice::from_ref(&b));
+
+        assert_eq!((merged, conflicts), (vec![b], 0));
+    }
+
+    #[test]
+    fn merge_trash_keeps_entries_unless_one_side_removed_them() {
+        let (a, b, c) = (
+            TrashedTask::new(task("a")),
+            TrashedTask::new(task("b")),
+            TrashedTask::new(task("c")),
+        );
+        let base = vec![a.clone(), b.clone()];
+        // We restored `a`, they trashed `c`.
+        let ours = vec![b.clone()];
+        let theirs = vec![a, b.clone(), c.clone()];
+
+        assert_eq!(merge_trash(&base, &ours, &theirs), vec![b, c]);
+    }
+
+    #[test]
+    fn merge_takes_settings_from_the_side_that_changed_them() {
+        let base = MyeonData::default();
+        let mut ours = base.clone();
+        let mut theirs = base.clone();
+        theirs.config.trash_days = 7;
+
+        assert_eq!(merge(&base, &mut ours, &theirs), 0);
+        assert_eq!(ours.config.trash_days, 7);
+
+        let mut ours = base.clone();
+        ours.config.trash_days = 1;
+        assert_eq!(merge(&base, &mut ours, &theirs), 1);
+        assert_eq!(ours.config.trash_days, 1);
+    }
+
+    #[test]
+    fn parse_upgrades_a_v1_board() {
+        let v1 = r#"{
+            "schema_version": 1,
+            "revision": 4,
+            "tasks": [{
+                "id": "7c2df886-14f1-49fa-8a4e-9b57518b2268",
+                "title": "Old task",
+                "status": "Doing",
+                "priority": "High",
+                "context": "Work",
+                "created_at": "2024-01-01T00:00:00Z"
+            }]
+        }"#;
+
+        let data = MyeonData::parse(v1).unwrap();
+
+        assert_eq!(data.schema_version, SCHEMA_VERSION);
+        assert_eq!(data.revision, 4);
+        assert_eq!(data.config.columns, default_columns());
+        assert_eq!(data.tasks[0].status, TaskStatus::new("Doing"));
+        assert_eq!(data.tasks[0].number, 1);
+        assert_eq!(data.next_task_number, 2);
+    }
+
+    #[test]
+    fn parse_upgrades_an_unversioned_board() {
+        let data = MyeonData::parse(r#"{ "tasks": [] }"#).unwrap();
+
+        assert_eq!(data.schema_version, SCHEMA_VERSION);
+        assert_eq!(data.revision, 0);
+        assert_eq!(data.config.columns, default_columns());
+    }
+
+    #[test]
+    fn parse_refuses_a_newer_schema() {
+        let newer = format!(
+            r#"{{ "schema_version": {}, "tasks": [] }}"#,
+            SCHEMA_VERSION + 1
+        );
+
+        assert!(matches!(
+            MyeonData::parse(&newer),
+            Err(AppError::UnsupportedSchema { .. })
+        ));
+    }
+
+    fn numbered(title: &str, number: u64, id: &str) -> Task {
+        Task {
+            number,
+            id: id.parse().unwrap(),
+            ..task(title)
+        }
+    }
+
+    #[test]
+    fn find_reference_takes_numbers_before_id_prefixes() {
+        let tasks = vec![
+            numbered("first", 1, "12aaaaaa-0000-4000-8000-000000000000"),
+            numbered("twelfth", 12, "bbbbbbbb-0000-4000-8000-000000000000"),
+        ];
+
+        assert_eq!(find_reference(&tasks, |t| t, "12").unwrap(), 1);
+        assert_eq!(find_reference(&tasks, |t| t, "#12").unwrap(), 1);
+        assert_eq!(find_reference(&tasks, |t| t, "12aa").unwrap(), 0);
+        assert_eq!(find_reference(&tasks, |t| t, "BBBB").unwrap(), 1);
+    }
+
+    #[test]
+    fn find_reference_reports_ambiguous_and_missing_tasks() {
+        let tasks = vec![
+            numbered("one", 1, "abcd0000-0000-4000-8000-000000000000"),
+            numbered("two", 2, "abcd1111-0000-4000-8000-000000000000"),
+        ];
+
+        assert!(matches!(
+            find_reference(&tasks, |t| t, "abcd"),
+            Err(AppError::AmbiguousTask { .. })
+        ));
+        assert!(matches!(
+            find_reference(&tasks, |t| t, "#7"),
+            Err(AppError::TaskNotFound(_))
+        ));
+        assert!(matches!(
+            find_reference(&tasks, |t| t, ""),
+            Err(AppError::TaskNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn a_quarantined_board_is_refused_until_it_is_replaced() {
+        let dir = std::env::temp_dir().join(format!("myeon-quarantine-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("tasks.json");
+        fs::write(&path, "{ not json").unwrap();
+
+        assert!(matches!(
+            MyeonData::load_read_only(&path),
+            Err(AppError::SerdeJson(_))
+        ));
+        assert!(path.exists());
+
+        assert!(matches!(
+            MyeonData::load(&path),
+            Err(AppError::CorruptData { .. })
+        ));
+        assert!(!path.exists());
+        assert!(matches!(
+            MyeonData::load_read_only(&path),
+            Err(AppError::Quarantined { .. })
+        ));
+        assert!(matches!(
+            MyeonData::load(&path),
+            Err(AppError::Quarantined { .. })
+        ));
+
+        let fresh = serde_json::to_string(&MyeonData::default()).unwrap();
+        fs::write(&path, fresh).unwrap();
+        assert!(MyeonData::load(&path).is_ok());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn restoring_a_quarantined_board_never_overwrites_one_in_its_place() {
+        let dir = std::env::temp_dir().join(format!("myeon-restore-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("tasks.json");
+        let quarantined = dir.join("tasks.json.corrupt-20260101T000000Z");
+        let board = serde_json::to_string(&MyeonData::default()).unwrap();
+        fs::write(&quarantined, &board).unwrap();
+        fs::write(&path, "newer board").unwrap();
+
+        assert!(MyeonData::restore_quarantined(&quarantined, &path).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "newer board");
+        assert!(quarantined.exists());
+
+        fs::remove_file(&path).unwrap();
+        assert!(MyeonData::restore_quarantined(&quarantined, &path).is_ok());
+        assert!(!quarantined.exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn unarchiving_keeps_the_finish_time_and_delays_auto_archiving() {
+        let mut data = MyeonData::default();
+        data.config.archive_after_days = 14;
+        let done = data.config.columns.last().unwrap().status();
+        let finished = chrono::Utc::now() - chrono::Duration::days(30);
+        let mut finished_task = task("shipped");
+        finished_task.status = done;
+        finished_task.completed_at = Some(finished);
+        data.tasks.push(finished_task);
+        data.normalize();
+        assert!(data.tasks[0].is_archived());
+
+        data.tasks[0].unarchive();
+        data.normalize();
+        assert!(!data.tasks[0].is_archived());
+        assert_eq!(data.tasks[0].completed_at, Some(finished));
+
+        data.tasks[0].unarchived_at = Some(chrono::Utc::now() - chrono::Duration::days(15));
+        data.normalize();
+        assert!(data.tasks[0].is_archived());
+    }
+
+    #[test]
+    fn tasks_of_a_removed_column_go_first_unless_archived() {
+        let mut data = MyeonData::default();
+        let mut active = task("active");
+        active.status = TaskStatus::new("Review");
+        let mut archived = task("archived");
+        archived.status = TaskStatus::new("Review");
+        archived.archive();
+        data.tasks = vec![active, archived];
+
+        data.normalize();
+
+        assert_eq!(data.tasks[0].status, data.config.first_status());
+        assert!(data.config.is_done(&data.tasks[1].status));
+    }
+}
@@ -1,4 +1,5 @@
 use std::io;
+use std::path::PathBuf;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -30,6 +31,38 @@ pub enum AppError {
     #[error("JSON serialization/deserialization error: {0}")]
     SerdeJson(#[from] serde_json::Error),
 
+    #[error("Board file is corrupt and was moved to {}: {source}", path.display())]
+    CorruptData {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+
+    #[error(
+        "Board file could not be read and was moved to {}; open myeon to restore it or start a new board",
+        path.display()
+    )]
+    Quarantined { path: PathBuf },
+
+    #[error(
+        "Board file uses schema version {found}, but this myeon only understands up to {supported}. Run `myeon update` to open it."
+    )]
+    UnsupportedSchema { found: u64, supported: u64 },
+
+    #[error("Could not determine where to keep the board; pass --board <path> or set MYEON_BOARD")]
+    NoBoardPath,
+
+    #[error("Board error: {0}")]
+    Board(String),
+
+    #[error("No task matches '{0}'")]
+    TaskNotFound(String),
+
+    #[error("'{reference}' matches more than one task ({candidates}); use its number instead")]
+    AmbiguousTask {
+        reference: String,
+        candidates: String,
+    },
+
     #[error("Self-update error: {0}")]
     SelfUpdate(#[from] self_update::errors::Error),
 }
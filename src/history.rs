@@ -0,0 +1,265 @@
+use crate::data::Task;
+use crate::storage;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// How many steps are kept for undo; older ones fall off.
+const MAX_STEPS: usize = 100;
+
+/// One task as it was before and after a step. `None` means it did not exist.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Change {
+    pub before: Option<Task>,
+    pub after: Option<Task>,
+}
+
+/// Everything one user action changed, e.g. "move 'Fix login' to Doing".
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Step {
+    /// Tells steps apart when journals of several instances are combined.
+    #[serde(default = "uuid::Uuid::new_v4")]
+    pub id: uuid::Uuid,
+    /// When the step was recorded.
+    #[serde(default)]
+    pub at: DateTime<Utc>,
+    pub label: String,
+    pub changes: Vec<Change>,
+}
+
+/// What came of an undo or redo.
+pub enum Outcome {
+    /// The step with this label was applied.
+    Applied(String),
+    /// The step with this label was dropped: its tasks changed since, e.g. in
+    /// another window, and applying it would overwrite that.
+    Stale(String),
+    /// There was nothing to undo or redo.
+    Empty,
+}
+
+/// Undo and redo stacks for a board, journaled with its backups (`tasks.json.journal`)
+/// so they survive a restart. Steps hold per-task changes rather than whole
+/// boards, so undoing one thing leaves other changes alone.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct History {
+    undo: Vec<Step>,
+    redo: Vec<Step>,
+    /// Ids of every step this instance has held, so steps it dropped are not
+    /// brought back from the journal on the next save.
+    #[serde(skip)]
+    seen: HashSet<uuid::Uuid>,
+}
+
+impl History {
+    /// Reads the journal for `board_path`. A missing or unreadable journal is an empty history.
+    pub fn load(board_path: &Path) -> Self {
+        let mut history: Self = fs::read_to_string(storage::journal_path(board_path))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        history.seen = history
+            .undo
+            .iter()
+            .chain(&history.redo)
+            .map(|s| s.id)
+            .collect();
+        history
+    }
+
+    /// Writes the journal, first taking in steps that other instances of the
+    /// board saved meanwhile, so no instance's history overwrites another's.
+    pub fn save(&mut self, board_path: &Path) -> Result<(), crate::error::AppError> {
+        let _lock = storage::lock(board_path)?;
+        let on_disk = Self::load(board_path);
+        for step in on_disk.undo {
+            if self.seen.insert(step.id) {
+                self.undo.push(step);
+            }
+        }
+        for step in on_disk.redo {
+            if self.seen.insert(step.id) {
+                self.redo.push(step);
+            }
+        }
+        // Newest on top of undo; the step undone last (the oldest) on top of redo.
+        self.undo.sort_by_key(|s| s.at);
+        self.redo.sort_by_key(|s| Reverse(s.at));
+        for stack in [&mut self.undo, &mut self.redo] {
+            let excess = stack.len().saturating_sub(MAX_STEPS);
+            stack.drain(..excess);
+        }
+
+        let content = serde_json::to_string(self)?;
+        storage::write_atomic(&storage::journal_path(board_path), content.as_bytes())?;
+        Ok(())
+    }
+
+    /// Records the difference between `before` and `after` for the tasks in `ids`.
+    /// A new step clears the redo stack.
+    pub fn record(&mut self, label: String, before: &[Task], after: &[Task], ids: &[uuid::Uuid]) {
+        let find = |tasks: &[Task], id: uuid::Uuid| tasks.iter().find(|t| t.id == id).cloned();
+        let changes: Vec<Change> = ids
+            .iter()
+            .map(|&id| Change {
+                before: find(before, id),
+                after: find(after, id),
+            })
+            .collect();
+        if changes.is_empty() {
+            return;
+        }
+        let step = Step {
+            id: uuid::Uuid::new_v4(),
+            at: Utc::now(),
+            label,
+            changes,
+        };
+        self.seen.insert(step.id);
+        self.undo.push(step);
+        if self.undo.len() > MAX_STEPS {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    /// Reverts the latest step on `tasks`, unless its tasks changed since.
+    pub fn undo(&mut self, tasks: &mut Vec<Task>) -> Outcome {
+        let Some(step) = self.undo.pop() else {
+            return Outcome::Empty;
+        };
+        if !step
+            .changes
+            .iter()
+            .all(|c| is_current(tasks, c.after.as_ref(), c.before.as_ref()))
+        {
+            return Outcome::Stale(step.label);
+        }
+        for change in step.changes.iter().rev() {
+            apply(tasks, change.after.as_ref(), change.before.clone());
+        }
+        let label = step.label.clone();
+        self.redo.push(step);
+        Outcome::Applied(label)
+    }
+
+    /// Re-applies the latest undone step on `tasks`, unless its tasks changed since.
+    pub fn redo(&mut self, tasks: &mut Vec<Task>) -> Outcome {
+        let Some(step) = self.redo.pop() else {
+            return Outcome::Empty;
+        };
+        if !step
+            .changes
+            .iter()
+            .all(|c| is_current(tasks, c.before.as_ref(), c.after.as_ref()))
+        {
+            return Outcome::Stale(step.label);
+        }
+        for change in &step.changes {
+            apply(tasks, change.before.as_ref(), change.after.clone());
+        }
+        let label = step.label.clone();
+        self.undo.push(step);
+        Outcome::Applied(label)
+    }
+}
+
+/// True if the task is still exactly `expected` in `tasks` (or still absent
+/// when `expected` is `None`). `other` is the task's state on the far side of
+/// the step, only used for its id when `expected` is `None`.
+fn is_current(tasks: &[Task], expected: Option<&Task>, other: Option<&Task>) -> bool {
+    let Some(id) = expected.or(other).map(|t| t.id) else {
+        return true;
+    };
+    tasks.iter().find(|t| t.id == id) == expected
+}
+
+/// Ids of tasks that were added, removed or changed between `before` and `after`.
+pub fn changed_ids(before: &[Task], after: &[Task]) -> Vec<uuid::Uuid> {
+    let mut ids: Vec<uuid::Uuid> = before
+        .iter()
+        .filter(|old| after.iter().find(|t| t.id == old.id) != Some(*old))
+        .map(|t| t.id)
+        .collect();
+    ids.extend(
+        after
+            .iter()
+            .filter(|new| !before.iter().any(|t| t.id == new.id))
+            .map(|t| t.id),
+    );
+    ids
+}
+
+/// Replaces the task `from` (if any) with `to`, or removes it when `to` is `None`.
+fn apply(tasks: &mut Vec<Task>, from: Option<&Task>, to: Option<Task>) {
+    let id = from.or(to.as_ref()).map(|t| t.id);
+    let position = id.and_then(|id| tasks.iter().position(|t| t.id == id));
+    match (position, to) {
+        (Some(i), Some(task)) => tasks[i] = task,
+        (Some(i), None) => {
+            tasks.remove(i);
+        }
+        (None, Some(task)) => tasks.push(task),
+        (None, None) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Priority, TaskStatus};
+
+    fn task(title: &str) -> Task {
+        Task::new(title, TaskStatus::new("todo"), Priority::Low, None, None)
+    }
+
+    fn record(history: &mut History, label: &str, before: &[Task], after: &[Task]) {
+        history.record(label.into(), before, after, &changed_ids(before, after));
+    }
+
+    #[test]
+    fn undo_and_redo_restore_each_side_of_a_step() {
+        let mut history = History::default();
+        let before = vec![task("a")];
+        let mut after = before.clone();
+        after[0].title = "renamed".into();
+        record(&mut history, "rename", &before, &after);
+
+        let mut tasks = after.clone();
+        assert!(matches!(history.undo(&mut tasks), Outcome::Applied(_)));
+        assert_eq!(tasks, before);
+        assert!(matches!(history.redo(&mut tasks), Outcome::Applied(_)));
+        assert_eq!(tasks, after);
+        assert!(matches!(history.redo(&mut tasks), Outcome::Empty));
+    }
+
+    #[test]
+    fn undo_drops_a_step_whose_task_changed_since() {
+        let mut history = History::default();
+        let before = vec![task("a")];
+        let mut after = before.clone();
+        after[0].title = "ours".into();
+        record(&mut history, "rename", &before, &after);
+
+        let mut tasks = after.clone();
+        tasks[0].title = "theirs".into();
+        let current = tasks.clone();
+
+        assert!(matches!(history.undo(&mut tasks), Outcome::Stale(_)));
+        assert_eq!(tasks, current);
+        assert!(matches!(history.undo(&mut tasks), Outcome::Empty));
+    }
+
+    #[test]
+    fn undo_of_an_add_is_stale_once_the_task_is_gone() {
+        let mut history = History::default();
+        let added = vec![task("new")];
+        record(&mut history, "add", &[], &added);
+
+        let mut tasks = Vec::new();
+        assert!(matches!(history.undo(&mut tasks), Outcome::Stale(_)));
+    }
+}
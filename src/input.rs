@@ -1,22 +1,29 @@
 use crate::app::{App, EditField, InputMode};
-use crate::data::Priority;
-use crossterm::event::{self, Event, KeyCode};
+use crate::data::{Priority, Urgency};
+use crossterm::event::{self, Event, KeyCode, KeyModifiers};
+use std::time::Duration;
+
+/// How long to wait for a key before handing control back to the main loop,
+/// which uses the gap to check the board file for outside changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
 
 pub fn handle_input(app: &mut App) -> std::io::Result<bool> {
+    if !event::poll(POLL_INTERVAL)? {
+        return Ok(false);
+    }
     if let Event::Key(key) = event::read()? {
+        app.status_message = None;
         match app.input_mode {
             InputMode::Normal => match key.code {
                 KeyCode::Char('q') => return Ok(true),
                 KeyCode::Char('a') => app.input_mode = InputMode::Editing,
-                KeyCode::Char('h') | KeyCode::Left => {
-                    if app.column_index > 0 {
-                        app.column_index -= 1;
-                    }
+                KeyCode::Char('h') | KeyCode::Left if app.column_index > 0 => {
+                    app.column_index -= 1;
                 }
-                KeyCode::Char('l') | KeyCode::Right => {
-                    if app.column_index < 3 {
-                        app.column_index += 1;
-                    }
+                KeyCode::Char('l') | KeyCode::Right
+                    if app.column_index + 1 < app.config.columns.len() =>
+                {
+                    app.column_index += 1;
                 }
                 KeyCode::Char('j') | KeyCode::Down => {
                     let max = app.get_current_column_tasks().len();
@@ -24,19 +31,176 @@ pub fn handle_input(app: &mut App) -> std::io::Result<bool> {
                         app.selected_task_index += 1;
                     }
                 }
-                KeyCode::Char('k') | KeyCode::Up => {
-                    if app.selected_task_index > 0 {
-                        app.selected_task_index -= 1;
-                    }
+                KeyCode::Char('k') | KeyCode::Up if app.selected_task_index > 0 => {
+                    app.selected_task_index -= 1;
                 }
                 KeyCode::Enter => app.move_task_forward(),
                 KeyCode::Backspace => app.move_task_backward(),
                 KeyCode::Char('c') => app.cycle_context(),
                 KeyCode::Char('d') => app.delete_task(),
                 KeyCode::Char('e') => app.start_edit(),
+                KeyCode::Char('b') => app.open_board_picker(),
+                KeyCode::Char('t') => app.open_trash(),
+                KeyCode::Char('A') => app.archive_selected(),
+                KeyCode::Char('v') => app.open_archive(),
+                KeyCode::Char('i') => app.open_detail(),
+                KeyCode::Char('s') => app.input_mode = InputMode::Stats,
+                KeyCode::Char('f') => app.input_mode = InputMode::Flow,
+                KeyCode::Char('m') => app.open_matrix(),
+                KeyCode::Char('J') => app.move_card(1),
+                KeyCode::Char('K') => app.move_card(-1),
+                KeyCode::Char('o') => app.cycle_sort(),
+                KeyCode::Char('/') => app.start_search(),
+                KeyCode::Char('V') => app.open_view_picker(),
+                KeyCode::Char('n') => app.jump_to_match(true),
+                KeyCode::Char('N') => app.jump_to_match(false),
+                KeyCode::Esc if !app.search_query.is_empty() => app.clear_search(),
+                KeyCode::Char('u') => app.undo(),
+                KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => app.redo(),
+                _ => {}
+            },
+            InputMode::BoardPicker => match key.code {
+                KeyCode::Char('j') | KeyCode::Down
+                    if app.board_list_index + 1 < app.board_list.len() =>
+                {
+                    app.board_list_index += 1;
+                }
+                KeyCode::Char('k') | KeyCode::Up if app.board_list_index > 0 => {
+                    app.board_list_index -= 1;
+                }
+                KeyCode::Enter => app.switch_board(),
+                KeyCode::Esc | KeyCode::Char('b') | KeyCode::Char('q') => {
+                    app.input_mode = InputMode::Normal;
+                }
+                _ => {}
+            },
+            InputMode::ViewPicker => match key.code {
+                KeyCode::Char('j') | KeyCode::Down if app.view_index < app.config.views.len() => {
+                    app.view_index += 1;
+                }
+                KeyCode::Char('k') | KeyCode::Up if app.view_index > 0 => {
+                    app.view_index -= 1;
+                }
+                KeyCode::Enter => app.select_view(),
+                KeyCode::Esc | KeyCode::Char('V') | KeyCode::Char('q') => {
+                    app.input_mode = InputMode::Normal;
+                }
+                _ => {}
+            },
+            InputMode::Search => match key.code {
+                KeyCode::Enter => app.input_mode = InputMode::Normal,
+                KeyCode::Esc => app.clear_search(),
+                KeyCode::Backspace => {
+                    let mut query = app.search_query.clone();
+                    query.pop();
+                    app.set_search(query);
+                }
+                KeyCode::Char(c) => {
+                    let query = format!("{}{}", app.search_query, c);
+                    app.set_search(query);
+                }
+                _ => {}
+            },
+            InputMode::Archive if app.archive_searching => match key.code {
+                KeyCode::Enter | KeyCode::Esc => app.archive_searching = false,
+                KeyCode::Backspace => {
+                    app.archive_query.pop();
+                    app.archive_index = 0;
+                }
+                KeyCode::Char(c) => {
+                    app.archive_query.push(c);
+                    app.archive_index = 0;
+                }
+                _ => {}
+            },
+            InputMode::Archive => match key.code {
+                KeyCode::Char('j') | KeyCode::Down
+                    if app.archive_index + 1 < app.archived_tasks().len() =>
+                {
+                    app.archive_index += 1;
+                }
+                KeyCode::Char('k') | KeyCode::Up if app.archive_index > 0 => {
+                    app.archive_index -= 1;
+                }
+                KeyCode::Char('/') => app.archive_searching = true,
+                KeyCode::Char('r') | KeyCode::Enter => app.unarchive_selected(),
+                KeyCode::Esc | KeyCode::Char('v') | KeyCode::Char('q') => {
+                    app.input_mode = InputMode::Normal;
+                }
+                _ => {}
+            },
+            InputMode::Matrix => match key.code {
+                KeyCode::Char('h') => app.shift_in_matrix(0, -1),
+                KeyCode::Char('l') => app.shift_in_matrix(0, 1),
+                KeyCode::Char('k') => app.shift_in_matrix(-1, 0),
+                KeyCode::Char('j') => app.shift_in_matrix(1, 0),
+                KeyCode::Down
+                    if app.matrix_index + 1 < app.matrix_tasks(app.matrix_quadrant).len() =>
+                {
+                    app.matrix_index += 1;
+                }
+                KeyCode::Up if app.matrix_index > 0 => app.matrix_index -= 1,
+                KeyCode::Tab | KeyCode::BackTab => {
+                    app.matrix_quadrant = if key.code == KeyCode::Tab {
+                        (app.matrix_quadrant + 1) % 4
+                    } else {
+                        (app.matrix_quadrant + 3) % 4
+                    };
+                    app.matrix_index = 0;
+                }
+                KeyCode::Char('c') => app.cycle_context(),
+                KeyCode::Char('u') => app.undo(),
+                KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => app.redo(),
+                KeyCode::Esc | KeyCode::Char('m') | KeyCode::Char('q') => {
+                    app.input_mode = InputMode::Normal;
+                }
                 _ => {}
             },
+            InputMode::Stats | InputMode::Flow => match key.code {
+                KeyCode::Char('c') => app.cycle_context(),
+                KeyCode::Char('s') if matches!(app.input_mode, InputMode::Flow) => {
+                    app.input_mode = InputMode::Stats;
+                }
+                KeyCode::Char('f') if matches!(app.input_mode, InputMode::Stats) => {
+                    app.input_mode = InputMode::Flow;
+                }
+                KeyCode::Esc | KeyCode::Char('s') | KeyCode::Char('f') | KeyCode::Char('q') => {
+                    app.input_mode = InputMode::Normal;
+                }
+                _ => {}
+            },
+            InputMode::Detail => match key.code {
+                KeyCode::Esc | KeyCode::Char('i') | KeyCode::Char('q') | KeyCode::Enter => {
+                    app.detail_task_id = None;
+                    app.input_mode = InputMode::Normal;
+                }
+                _ => {}
+            },
+            InputMode::Trash => match key.code {
+                KeyCode::Char('j') | KeyCode::Down if app.trash_index + 1 < app.trash.len() => {
+                    app.trash_index += 1;
+                }
+                KeyCode::Char('k') | KeyCode::Up if app.trash_index > 0 => {
+                    app.trash_index -= 1;
+                }
+                KeyCode::Char('r') | KeyCode::Enter => app.restore_trashed(),
+                KeyCode::Char('x') => app.purge_trashed(),
+                KeyCode::Esc | KeyCode::Char('t') | KeyCode::Char('q') => {
+                    app.input_mode = InputMode::Normal;
+                }
+                _ => {}
+            },
+            InputMode::Confirm => match key.code {
+                KeyCode::Char('y') | KeyCode::Enter => app.confirm_pending(),
+                _ => app.cancel_pending(),
+            },
             InputMode::Editing => handle_editing_key(key, app),
+            InputMode::Recovery => match key.code {
+                KeyCode::Char('q') => return Ok(true),
+                KeyCode::Char('r') => app.recover(),
+                KeyCode::Char('n') => app.start_fresh(),
+                _ => {}
+            },
         }
     }
     Ok(false)
@@ -49,15 +213,17 @@ fn handle_editing_key(key: event::KeyEvent, app: &mut App) {
                 EditField::Title => EditField::Description,
                 EditField::Description => EditField::Context,
                 EditField::Context => EditField::Priority,
-                EditField::Priority => EditField::Title,
+                EditField::Priority => EditField::Urgency,
+                EditField::Urgency => EditField::Title,
             }
         }
         KeyCode::BackTab => {
             app.active_edit_field = match app.active_edit_field {
-                EditField::Title => EditField::Priority,
+                EditField::Title => EditField::Urgency,
                 EditField::Description => EditField::Title,
                 EditField::Context => EditField::Description,
                 EditField::Priority => EditField::Context,
+                EditField::Urgency => EditField::Priority,
             }
         }
         KeyCode::Up | KeyCode::Down if matches!(app.active_edit_field, EditField::Context) => {
@@ -81,6 +247,7 @@ fn handle_editing_key(key: event::KeyEvent, app: &mut App) {
             app.editing_description.clear();
             app.editing_context.clear();
             app.editing_priority = Priority::Low;
+            app.editing_urgency = Urgency::Low;
             app.context_list_index = 0;
         }
         KeyCode::Char(c) => match app.active_edit_field {
@@ -93,6 +260,12 @@ fn handle_editing_key(key: event::KeyEvent, app: &mut App) {
                 '3' => app.editing_priority = Priority::High,
                 _ => {}
             },
+            EditField::Urgency => match c {
+                '1' => app.editing_urgency = Urgency::Low,
+                '2' => app.editing_urgency = Urgency::Medium,
+                '3' => app.editing_urgency = Urgency::High,
+                _ => {}
+            },
         },
         KeyCode::Backspace => match app.active_edit_field {
             EditField::Title => {
@@ -104,7 +277,7 @@ fn handle_editing_key(key: event::KeyEvent, app: &mut App) {
             EditField::Context => {
                 app.editing_context.pop();
             }
-            EditField::Priority => {}
+            EditField::Priority | EditField::Urgency => {}
         },
         _ => {}
     }
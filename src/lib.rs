@@ -1,7 +1,14 @@
 pub mod app;
+pub mod boards;
 pub mod cli;
 pub mod colours;
+pub mod commands;
 pub mod data;
 pub mod error;
+pub mod history;
 pub mod input;
+pub mod output;
+pub mod query;
+pub mod stats;
+pub mod storage;
 pub mod ui;
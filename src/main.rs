@@ -7,10 +7,12 @@ use crossterm::{
 use myeon::{
     app::App,
     cli::{Cli, Commands},
-    colours, input, ui,
+    colours, commands,
+    data::{MyeonData, TaskEdit},
+    input, ui,
 };
 use ratatui::{Terminal, backend::CrosstermBackend};
-use std::{error::Error, io};
+use std::{error::Error, io, path::PathBuf};
 
 fn main() {
     let cli = Cli::parse();
@@ -35,23 +37,121 @@ pub fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
             println!("Update status: `{}`!", status.version());
             Ok(())
         }
-        None => run_tui(),
+        Some(Commands::Add {
+            title,
+            context,
+            priority,
+            urgency,
+            description,
+            column,
+        }) => {
+            let board = MyeonData::resolve_path(cli.board.as_deref())?;
+            Ok(commands::add(
+                &board,
+                title,
+                context,
+                priority,
+                urgency,
+                description,
+                column,
+            )?)
+        }
+        Some(Commands::List {
+            status,
+            context,
+            filter,
+            view,
+            format,
+        }) => {
+            let board = MyeonData::resolve_path(cli.board.as_deref())?;
+            Ok(commands::list(
+                &board, status, context, filter, view, format,
+            )?)
+        }
+        Some(Commands::Move {
+            task,
+            column,
+            force,
+        }) => {
+            let board = MyeonData::resolve_path(cli.board.as_deref())?;
+            Ok(commands::move_task(&board, &task, &column, force)?)
+        }
+        Some(Commands::Edit {
+            task,
+            title,
+            description,
+            context,
+            priority,
+            urgency,
+        }) => {
+            let board = MyeonData::resolve_path(cli.board.as_deref())?;
+            let edit = TaskEdit {
+                title,
+                description,
+                context,
+                priority,
+                urgency,
+            };
+            Ok(commands::edit(&board, &task, edit)?)
+        }
+        Some(Commands::Rm { task }) => {
+            let board = MyeonData::resolve_path(cli.board.as_deref())?;
+            Ok(commands::remove(&board, &task)?)
+        }
+        Some(Commands::Stats { context, weeks }) => {
+            let board = MyeonData::resolve_path(cli.board.as_deref())?;
+            Ok(commands::stats(&board, context, weeks)?)
+        }
+        Some(Commands::Cfd {
+            context,
+            weeks,
+            format,
+        }) => {
+            let board = MyeonData::resolve_path(cli.board.as_deref())?;
+            Ok(commands::cfd(&board, context, weeks, format)?)
+        }
+        Some(Commands::Archive { action }) => {
+            let board = MyeonData::resolve_path(cli.board.as_deref())?;
+            Ok(commands::archive(&board, action)?)
+        }
+        Some(Commands::Trash { action }) => {
+            let board = MyeonData::resolve_path(cli.board.as_deref())?;
+            Ok(commands::trash(&board, action)?)
+        }
+        Some(Commands::Views { action }) => {
+            let board = MyeonData::resolve_path(cli.board.as_deref())?;
+            Ok(commands::views(&board, action)?)
+        }
+        Some(Commands::Columns { action }) => {
+            let board = MyeonData::resolve_path(cli.board.as_deref())?;
+            Ok(commands::columns(&board, action)?)
+        }
+        Some(Commands::Boards { action }) => {
+            let current = MyeonData::resolve_path(cli.board.as_deref()).ok();
+            Ok(commands::boards(action, current.as_deref())?)
+        }
+        Some(Commands::Restore { backup }) => {
+            let board = MyeonData::resolve_path(cli.board.as_deref())?;
+            Ok(commands::restore(&board, backup)?)
+        }
+        None => run_tui(MyeonData::resolve_path(cli.board.as_deref())?),
     }
 }
 
-fn run_tui() -> Result<(), Box<dyn Error>> {
+fn run_tui(board: PathBuf) -> Result<(), Box<dyn Error>> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let mut app = App::new();
+    let mut app = App::new(board);
     loop {
-        terminal.draw(|f| ui::render(f, &app))?;
+        terminal.draw(|f| ui::render(f, &mut app))?;
         if input::handle_input(&mut app)? {
             break;
         }
+        app.reload_if_changed();
     }
 
     disable_raw_mode()?;
@@ -0,0 +1,228 @@
+use crate::colours;
+use crate::data::{Column, Priority, Task, Urgency};
+use crate::stats::CumulativeFlow;
+use clap::ValueEnum;
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum OutputFormat {
+    /// Aligned columns for reading, coloured on a terminal.
+    #[default]
+    Table,
+    /// One JSON array of task records.
+    Json,
+    /// One JSON task record per line.
+    Ndjson,
+    /// Tab-separated task records, one per line, no header.
+    Tsv,
+}
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum FlowFormat {
+    /// Aligned columns for reading.
+    #[default]
+    Table,
+    /// Comma-separated, with a header row of `date` and the column names.
+    Csv,
+}
+
+/// A task as it appears in machine-readable output. The field names and
+/// their order are a stable interface for scripts; only additions are allowed.
+///
+/// | field        | meaning                                           |
+/// |--------------|---------------------------------------------------|
+/// | `id`         | full UUID of the task                             |
+/// | `short_id`   | first 8 characters of `id`, accepted wherever a task is expected |
+/// | `title`      | task title                                        |
+/// | `status`     | id of the column the task is in                   |
+/// | `column`     | display name of that column                       |
+/// | `priority`   | `high`, `medium` or `low`                         |
+/// | `context`    | context, e.g. `Work`                              |
+/// | `created_at` | RFC 3339 timestamp in UTC                         |
+/// | `urgency`    | `high`, `medium` or `low`                         |
+/// | `number`     | task number (`#12`), accepted wherever a task is expected |
+///
+/// TSV output has the same fields in the same order.
+#[derive(Serialize)]
+pub struct TaskRecord<'a> {
+    pub id: String,
+    pub short_id: String,
+    pub title: &'a str,
+    pub status: &'a str,
+    pub column: &'a str,
+    pub priority: &'static str,
+    pub context: &'a str,
+    pub created_at: String,
+    pub urgency: &'static str,
+    pub number: u64,
+}
+
+impl<'a> TaskRecord<'a> {
+    pub fn new(task: &'a Task, column: &'a Column) -> Self {
+        let id = task.id.to_string();
+        Self {
+            short_id: id[..8].to_string(),
+            id,
+            title: &task.title,
+            status: &task.status.0,
+            column: &column.name,
+            priority: priority_name(&task.priority),
+            context: &task.context,
+            created_at: task
+                .created_at
+                .to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            urgency: urgency_name(&task.urgency),
+            number: task.number,
+        }
+    }
+}
+
+pub fn urgency_name(urgency: &Urgency) -> &'static str {
+    match urgency {
+        Urgency::High => "high",
+        Urgency::Medium => "medium",
+        Urgency::Low => "low",
+    }
+}
+
+pub fn priority_name(priority: &Priority) -> &'static str {
+    match priority {
+        Priority::High => "high",
+        Priority::Medium => "medium",
+        Priority::Low => "low",
+    }
+}
+
+/// Renders task records in the requested format, ready to print.
+pub fn render(records: &[TaskRecord], format: OutputFormat) -> Result<String, serde_json::Error> {
+    let mut out = String::new();
+    match format {
+        OutputFormat::Json => {
+            out = serde_json::to_string_pretty(records)?;
+            out.push('\n');
+        }
+        OutputFormat::Ndjson => {
+            for record in records {
+                out.push_str(&serde_json::to_string(record)?);
+                out.push('\n');
+            }
+        }
+        OutputFormat::Tsv => {
+            for r in records {
+                let number = r.number.to_string();
+                let fields = [
+                    r.id.as_str(),
+                    &r.short_id,
+                    r.title,
+                    r.status,
+                    r.column,
+                    r.priority,
+                    r.context,
+                    &r.created_at,
+                    r.urgency,
+                    &number,
+                ];
+                let fields: Vec<String> = fields.iter().map(|f| tsv_field(f)).collect();
+                out.push_str(&fields.join("\t"));
+                out.push('\n');
+            }
+        }
+        OutputFormat::Table => out = render_table(records),
+    }
+    Ok(out)
+}
+
+fn render_table(records: &[TaskRecord]) -> String {
+    colours::detect_for_stdout();
+    let id_width = records
+        .iter()
+        .map(|r| r.number.to_string().len())
+        .max()
+        .unwrap_or(0)
+        .max(2);
+    let column_width = records
+        .iter()
+        .map(|r| r.column.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(6);
+
+    let mut out = String::new();
+    out.push_str(&colours::heading(&format!(
+        "{:<id_width$}  {:<column_width$}  {:<8}  {:<10}  {}",
+        "ID", "COLUMN", "PRIORITY", "CONTEXT", "TITLE"
+    )));
+    out.push('\n');
+    for r in records {
+        let priority = format!("{:<8}", r.priority);
+        let priority = match r.priority {
+            "high" => colours::urgent(&priority),
+            "medium" => colours::notice(&priority),
+            _ => colours::muted(&priority),
+        };
+        out.push_str(&format!(
+            "{}  {:<column_width$}  {}  {:<10}  {}\n",
+            colours::muted(&format!("{:<id_width$}", r.number)),
+            r.column,
+            priority,
+            r.context,
+            r.title
+        ));
+    }
+    out
+}
+
+/// Keeps a value on one TSV line in one field.
+fn tsv_field(value: &str) -> String {
+    value.replace(['\t', '\n', '\r'], " ")
+}
+
+/// Renders a cumulative flow as one row per day.
+pub fn render_flow(flow: &CumulativeFlow, format: FlowFormat) -> String {
+    let mut out = String::new();
+    match format {
+        FlowFormat::Csv => {
+            let header: Vec<String> = std::iter::once("date".to_string())
+                .chain(flow.columns.iter().map(|c| csv_field(c)))
+                .collect();
+            out.push_str(&header.join(","));
+            out.push('\n');
+            for (date, counts) in &flow.days {
+                out.push_str(&date.format("%Y-%m-%d").to_string());
+                for count in counts {
+                    out.push_str(&format!(",{}", count));
+                }
+                out.push('\n');
+            }
+        }
+        FlowFormat::Table => {
+            let widths: Vec<usize> = flow
+                .columns
+                .iter()
+                .map(|c| c.chars().count().max(3))
+                .collect();
+            out.push_str("date      ");
+            for (column, width) in flow.columns.iter().zip(&widths) {
+                out.push_str(&format!("  {:>width$}", column, width = width));
+            }
+            out.push('\n');
+            for (date, counts) in &flow.days {
+                out.push_str(&date.format("%Y-%m-%d").to_string());
+                for (count, width) in counts.iter().zip(&widths) {
+                    out.push_str(&format!("  {:>width$}", count, width = width));
+                }
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
+/// Quotes a CSV field when it needs it.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
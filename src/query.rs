@@ -0,0 +1,346 @@
+use crate::data::{BoardConfig, Priority, Task, Urgency};
+use chrono::{DateTime, Duration, Utc};
+use std::fmt;
+use std::str::FromStr;
+
+/// A parsed filter expression such as `ctx:Work prio:high status:doing age>7d tag:bug "login"`.
+/// It is a list of terms separated by spaces, and a task must match every one:
+///
+/// * `ctx:<name>` (or `context:`) – the task's context.
+/// * `prio:<high|medium|low>` (or `priority:`) and `urg:` (or `urgency:`).
+/// * `status:<column>` (or `col:`) – the column's name or id.
+/// * `tag:<name>` – a `#name` hashtag in the title or description.
+/// * `age>7d`, `age<12h`, `age>=2w` – time since the task was created, in
+///   minutes (`m`), hours (`h`), days (`d`, the default) or weeks (`w`).
+/// * anything else, quoted or not – text in the title, description or context.
+///
+/// An empty query matches every task.
+#[derive(Debug, Clone, Default)]
+pub struct Query {
+    source: String,
+    terms: Vec<Term>,
+}
+
+#[derive(Debug, Clone)]
+enum Term {
+    Context(String),
+    Priority(Priority),
+    Urgency(Urgency),
+    Status(String),
+    Tag(String),
+    Age(Comparison, Duration),
+    Text(String),
+}
+
+/// How a task's age is compared in `age>7d` and friends.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comparison {
+    Over,
+    AtLeast,
+    Under,
+    AtMost,
+}
+
+impl Query {
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Checks the query against a board: every `status:` has to name one of its columns.
+    pub fn check(&self, config: &BoardConfig) -> Result<(), String> {
+        for term in &self.terms {
+            if let Term::Status(column) = term
+                && config.find_column(column).is_none()
+            {
+                return Err(format!("no column named '{}'", column));
+            }
+        }
+        Ok(())
+    }
+
+    /// True if `task` matches every term. Columns are looked up in `config` and
+    /// ages are measured up to `now`.
+    pub fn matches(&self, task: &Task, config: &BoardConfig, now: DateTime<Utc>) -> bool {
+        self.terms.iter().all(|term| match term {
+            Term::Context(context) => task.context.eq_ignore_ascii_case(context),
+            Term::Priority(priority) => task.priority == *priority,
+            Term::Urgency(urgency) => task.urgency == *urgency,
+            Term::Status(column) => config
+                .find_column(column)
+                .is_some_and(|i| config.columns[i].id == task.status.0),
+            Term::Tag(tag) => {
+                has_tag(&task.title, tag)
+                    || task.description.as_ref().is_some_and(|d| has_tag(d, tag))
+            }
+            Term::Age(comparison, limit) => {
+                let age = now - task.created_at;
+                match comparison {
+                    Comparison::Over => age > *limit,
+                    Comparison::AtLeast => age >= *limit,
+                    Comparison::Under => age < *limit,
+                    Comparison::AtMost => age <= *limit,
+                }
+            }
+            Term::Text(text) => task.matches_text(text),
+        })
+    }
+}
+
+impl FromStr for Query {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let terms = tokenize(s)?
+            .into_iter()
+            .map(|(token, quoted)| {
+                if quoted {
+                    Ok(Term::Text(token))
+                } else {
+                    parse_term(&token)
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Query {
+            source: s.trim().to_string(),
+            terms,
+        })
+    }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+/// Splits on spaces outside double quotes. The flag is set for tokens that are
+/// entirely quoted, which always count as text.
+fn tokenize(s: &str) -> Result<Vec<(String, bool)>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = s.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let mut token = String::new();
+        let mut quoted = c == '"';
+        let mut in_quotes = false;
+        while let Some(&c) = chars.peek() {
+            if c == '"' {
+                in_quotes = !in_quotes;
+            } else if c.is_whitespace() && !in_quotes {
+                break;
+            } else {
+                if !in_quotes {
+                    quoted = false;
+                }
+                token.push(c);
+            }
+            chars.next();
+        }
+        if in_quotes {
+            return Err(format!("missing closing quote after '{}'", token));
+        }
+        tokens.push((token, quoted));
+    }
+    Ok(tokens)
+}
+
+fn parse_term(token: &str) -> Result<Term, String> {
+    if let Some(comparison) = token.strip_prefix("age") {
+        let operators = [
+            (">=", Comparison::AtLeast),
+            ("<=", Comparison::AtMost),
+            (">", Comparison::Over),
+            ("<", Comparison::Under),
+        ];
+        let Some((comparison, amount)) = operators
+            .into_iter()
+            .find_map(|(operator, c)| comparison.strip_prefix(operator).map(|rest| (c, rest)))
+        else {
+            return Ok(Term::Text(token.to_string()));
+        };
+        return Ok(Term::Age(comparison, parse_age(amount)?));
+    }
+
+    let Some((key, value)) = token.split_once(':') else {
+        return Ok(Term::Text(token.to_string()));
+    };
+    if value.is_empty() {
+        return Err(format!("'{}' needs a value after the colon", key));
+    }
+    match key.to_ascii_lowercase().as_str() {
+        "ctx" | "context" => Ok(Term::Context(value.to_string())),
+        "prio" | "priority" => Ok(Term::Priority(value.parse()?)),
+        "urg" | "urgency" => Ok(Term::Urgency(value.parse()?)),
+        "status" | "col" | "column" => Ok(Term::Status(value.to_string())),
+        "tag" => Ok(Term::Tag(value.trim_start_matches('#').to_string())),
+        _ => Err(format!(
+            "unknown filter '{}:' (use ctx, prio, urg, status, tag or age)",
+            key
+        )),
+    }
+}
+
+fn parse_age(amount: &str) -> Result<Duration, String> {
+    let split = amount
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(amount.len());
+    let (number, unit) = amount.split_at(split);
+    let number: i64 = number
+        .parse()
+        .map_err(|_| format!("'{}' is not an age like 7d or 12h", amount))?;
+    let duration = match unit {
+        "m" => Duration::try_minutes(number),
+        "h" => Duration::try_hours(number),
+        "" | "d" => Duration::try_days(number),
+        "w" => Duration::try_weeks(number),
+        _ => return Err(format!("unknown age unit '{}' (use m, h, d or w)", unit)),
+    };
+    duration.ok_or_else(|| format!("'{}' is too long an age", amount))
+}
+
+fn has_tag(text: &str, tag: &str) -> bool {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '#' || c == '-' || c == '_'))
+        .filter_map(|word| word.strip_prefix('#'))
+        .any(|word| word.eq_ignore_ascii_case(tag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::TaskStatus;
+
+    fn tokens(s: &str) -> Vec<(String, bool)> {
+        tokenize(s).unwrap()
+    }
+
+    fn aged(days: i64, now: DateTime<Utc>) -> Task {
+        let mut task = Task::new("a", TaskStatus::new("Doing"), Priority::Low, None, None);
+        task.created_at = now - Duration::days(days);
+        task
+    }
+
+    #[test]
+    fn tokenize_splits_on_spaces_outside_quotes() {
+        assert_eq!(
+            tokens(r#"  ctx:Work "login page"  tag:"a b" "#),
+            vec![
+                ("ctx:Work".to_string(), false),
+                ("login page".to_string(), true),
+                ("tag:a b".to_string(), false),
+            ]
+        );
+        assert!(tokens("").is_empty());
+    }
+
+    #[test]
+    fn tokenize_refuses_an_unclosed_quote() {
+        assert!(tokenize(r#"ctx:Work "login"#).is_err());
+    }
+
+    #[test]
+    fn parse_term_knows_every_key() {
+        assert!(matches!(parse_term("ctx:Work"), Ok(Term::Context(c)) if c == "Work"));
+        assert!(matches!(parse_term("context:Work"), Ok(Term::Context(_))));
+        assert!(matches!(
+            parse_term("prio:high"),
+            Ok(Term::Priority(Priority::High))
+        ));
+        assert!(matches!(
+            parse_term("priority:low"),
+            Ok(Term::Priority(Priority::Low))
+        ));
+        assert!(matches!(
+            parse_term("urg:medium"),
+            Ok(Term::Urgency(Urgency::Medium))
+        ));
+        assert!(matches!(
+            parse_term("urgency:high"),
+            Ok(Term::Urgency(Urgency::High))
+        ));
+        for key in ["status", "col", "column", "STATUS"] {
+            let term = parse_term(&format!("{}:doing", key));
+            assert!(matches!(term, Ok(Term::Status(c)) if c == "doing"));
+        }
+        assert!(matches!(parse_term("tag:#bug"), Ok(Term::Tag(t)) if t == "bug"));
+        assert!(matches!(
+            parse_term("age>=2w"),
+            Ok(Term::Age(Comparison::AtLeast, d)) if d == Duration::weeks(2)
+        ));
+        assert!(matches!(
+            parse_term("age<12h"),
+            Ok(Term::Age(Comparison::Under, _))
+        ));
+        assert!(matches!(parse_term("login"), Ok(Term::Text(t)) if t == "login"));
+        assert!(matches!(parse_term("ageless"), Ok(Term::Text(_))));
+    }
+
+    #[test]
+    fn parse_term_refuses_unknown_keys_and_empty_values() {
+        assert!(parse_term("owner:me").is_err());
+        assert!(parse_term("ctx:").is_err());
+        assert!(parse_term("prio:urgent").is_err());
+    }
+
+    #[test]
+    fn parse_age_reads_every_unit() {
+        assert_eq!(parse_age("30m"), Ok(Duration::minutes(30)));
+        assert_eq!(parse_age("12h"), Ok(Duration::hours(12)));
+        assert_eq!(parse_age("7d"), Ok(Duration::days(7)));
+        assert_eq!(parse_age("7"), Ok(Duration::days(7)));
+        assert_eq!(parse_age("2w"), Ok(Duration::weeks(2)));
+        assert!(parse_age("3y").is_err());
+        assert!(parse_age("d").is_err());
+    }
+
+    #[test]
+    fn parse_age_refuses_an_age_too_long_to_hold() {
+        assert!(parse_age("99999999999999d").is_err());
+        assert!(parse_age("99999999999999999999d").is_err());
+        assert!("age>99999999999999d".parse::<Query>().is_err());
+    }
+
+    #[test]
+    fn age_bounds_with_equals_are_inclusive() {
+        let config = BoardConfig::default();
+        let now = Utc::now();
+        let week_old = aged(7, now);
+        let matches = |q: &str| q.parse::<Query>().unwrap().matches(&week_old, &config, now);
+
+        assert!(matches("age>=7d"));
+        assert!(matches("age<=7d"));
+        assert!(!matches("age>7d"));
+        assert!(!matches("age<7d"));
+    }
+
+    #[test]
+    fn check_reports_unknown_columns() {
+        let config = BoardConfig::default();
+        assert!(
+            "status:doing"
+                .parse::<Query>()
+                .unwrap()
+                .check(&config)
+                .is_ok()
+        );
+        assert!(
+            "status:Review"
+                .parse::<Query>()
+                .unwrap()
+                .check(&config)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn has_tag_matches_whole_hashtags() {
+        assert!(has_tag("Fix #bug in login", "bug"));
+        assert!(has_tag("(#Bug)", "bug"));
+        assert!(has_tag("#front-end, #ui", "front-end"));
+        assert!(!has_tag("Fix #bugs", "bug"));
+        assert!(!has_tag("Fix bug", "bug"));
+        assert!(!has_tag("a#bug", "bug"));
+    }
+}
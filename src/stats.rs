@@ -0,0 +1,345 @@
+use crate::data::{BoardConfig, Task, TrashedTask};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Utc};
+
+/// Weeks of history shown when no other number is asked for.
+pub const DEFAULT_WEEKS: usize = 8;
+/// Longest history `--weeks` accepts, ten years.
+pub const MAX_WEEKS: u64 = 520;
+
+/// Flow metrics for a board. A task is *started* when it first enters an in-progress
+/// column (see `BoardConfig::in_progress`) and *finished* when it reaches the last column.
+pub struct Stats {
+    /// Tasks finished per week, oldest week first. Weeks start on Monday.
+    pub throughput: Vec<(NaiveDate, usize)>,
+    /// Creation to finish.
+    pub lead_time: Option<Summary>,
+    /// Start to finish.
+    pub cycle_time: Option<Summary>,
+    /// Tasks per column, in board order.
+    pub wip: Vec<ColumnLoad>,
+    /// Tasks in the in-progress columns, longest waiting first.
+    pub ageing: Vec<Ageing>,
+}
+
+pub struct Summary {
+    pub count: usize,
+    pub average: Duration,
+    pub median: Duration,
+}
+
+pub struct ColumnLoad {
+    pub name: String,
+    pub count: usize,
+    pub limit: Option<usize>,
+}
+
+pub struct Ageing {
+    pub number: u64,
+    pub title: String,
+    pub column: String,
+    /// Time since the task entered its current column.
+    pub age: Duration,
+}
+
+/// Works out the stats for `tasks`, only counting tasks in `context` when given.
+/// Archived tasks count as finished work but not towards WIP.
+pub fn compute(
+    config: &BoardConfig,
+    tasks: &[Task],
+    context: Option<&str>,
+    weeks: usize,
+    now: DateTime<Utc>,
+) -> Stats {
+    let tasks: Vec<&Task> = tasks
+        .iter()
+        .filter(|t| context.is_none_or(|c| t.context.eq_ignore_ascii_case(c)))
+        .collect();
+
+    let this_week = week_start(now.with_timezone(&Local).date_naive());
+    let mut throughput: Vec<(NaiveDate, usize)> = (0..weeks)
+        .rev()
+        .map(|i| (this_week - Duration::weeks(i as i64), 0))
+        .collect();
+    let in_progress = config.in_progress();
+    let mut lead_times = Vec::new();
+    let mut cycle_times = Vec::new();
+    for task in &tasks {
+        let Some(completed) = task.completed_at else {
+            continue;
+        };
+        let week = week_start(completed.with_timezone(&Local).date_naive());
+        if let Some((_, count)) = throughput.iter_mut().find(|(start, _)| *start == week) {
+            *count += 1;
+        }
+        lead_times.push(completed - task.created_at);
+        let started = task.transitions.iter().find(|t| {
+            config
+                .column_index(&t.to)
+                .is_some_and(|i| in_progress.contains(&i))
+        });
+        if let Some(started) = started {
+            cycle_times.push(completed - started.at);
+        }
+    }
+
+    let active: Vec<&&Task> = tasks.iter().filter(|t| !t.is_archived()).collect();
+    let wip = config
+        .columns
+        .iter()
+        .map(|column| ColumnLoad {
+            name: column.name.clone(),
+            count: active.iter().filter(|t| t.status.0 == column.id).count(),
+            limit: column.wip_limit,
+        })
+        .collect();
+
+    let mut ageing: Vec<Ageing> = active
+        .iter()
+        .filter_map(|task| {
+            let column = config
+                .column_index(&task.status)
+                .filter(|i| in_progress.contains(i))
+                .map(|i| &config.columns[i])?;
+            Some(Ageing {
+                number: task.number,
+                title: task.title.clone(),
+                column: column.name.clone(),
+                age: now - task.entered_column_at(),
+            })
+        })
+        .collect();
+    ageing.sort_by_key(|a| std::cmp::Reverse(a.age));
+
+    Stats {
+        throughput,
+        lead_time: summarize(lead_times),
+        cycle_time: summarize(cycle_times),
+        wip,
+        ageing,
+    }
+}
+
+/// Tasks per column for each day of a period, for a cumulative flow diagram.
+pub struct CumulativeFlow {
+    /// Column names in board order.
+    pub columns: Vec<String>,
+    /// One entry per day, oldest first, with a count per column.
+    pub days: Vec<(NaiveDate, Vec<usize>)>,
+}
+
+/// Replays the task histories to count what sat in each column at the end of
+/// every day (local time) of the last `weeks` weeks; for today that is now.
+/// Deleted tasks count until they were deleted.
+pub fn cumulative_flow(
+    config: &BoardConfig,
+    tasks: &[Task],
+    trash: &[TrashedTask],
+    context: Option<&str>,
+    weeks: usize,
+    now: DateTime<Utc>,
+) -> CumulativeFlow {
+    let in_context = |t: &Task| context.is_none_or(|c| t.context.eq_ignore_ascii_case(c));
+    let tasks: Vec<(&Task, Option<DateTime<Utc>>)> = tasks
+        .iter()
+        .filter(|t| in_context(t))
+        .map(|t| (t, None))
+        .chain(
+            trash
+                .iter()
+                .filter(|t| in_context(&t.task))
+                .map(|t| (&t.task, Some(t.deleted_at))),
+        )
+        .collect();
+
+    let today = now.with_timezone(&Local).date_naive();
+    let days = (0..=weeks * 7)
+        .rev()
+        .map(|back| {
+            let date = today - Duration::days(back as i64);
+            // Midnight starting the next day, or now for today.
+            let time = (date + Duration::days(1))
+                .and_hms_opt(0, 0, 0)
+                .and_then(|midnight| midnight.and_local_timezone(Local).earliest())
+                .map_or(now, |end| end.with_timezone(&Utc).min(now));
+            let mut counts = vec![0; config.columns.len()];
+            for (task, deleted_at) in &tasks {
+                if deleted_at.is_some_and(|deleted| deleted <= time) {
+                    continue;
+                }
+                if let Some(index) = task
+                    .status_at(time)
+                    .and_then(|status| config.column_index(status))
+                {
+                    counts[index] += 1;
+                }
+            }
+            (date, counts)
+        })
+        .collect();
+
+    CumulativeFlow {
+        columns: config.columns.iter().map(|c| c.name.clone()).collect(),
+        days,
+    }
+}
+
+fn summarize(mut durations: Vec<Duration>) -> Option<Summary> {
+    if durations.is_empty() {
+        return None;
+    }
+    durations.sort();
+    let count = durations.len();
+    let total: Duration = durations.iter().sum();
+    let middle = count / 2;
+    let median = if count.is_multiple_of(2) {
+        (durations[middle - 1] + durations[middle]) / 2
+    } else {
+        durations[middle]
+    };
+    Some(Summary {
+        count,
+        average: total / count as i32,
+        median,
+    })
+}
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday().into())
+}
+
+/// A duration the way people talk about task ages: "3.5d", "6h", "20m".
+pub fn format_duration(duration: Duration) -> String {
+    let minutes = duration.num_minutes().max(0);
+    if minutes >= 24 * 60 {
+        format!("{:.1}d", minutes as f64 / (24.0 * 60.0))
+    } else if minutes >= 60 {
+        format!("{}h", minutes / 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Priority, Transition};
+
+    /// `hour:minute` on `date` in the local time zone.
+    fn local(date: NaiveDate, hour: u32, minute: u32) -> DateTime<Utc> {
+        date.and_hms_opt(hour, minute, 0)
+            .and_then(|time| time.and_local_timezone(Local).earliest())
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    /// A task created `days_ago` that moved through `moves` (column index, days ago).
+    fn task_through(
+        config: &BoardConfig,
+        now: DateTime<Utc>,
+        days_ago: i64,
+        moves: &[(usize, i64)],
+    ) -> Task {
+        let mut task = Task::new("a", config.first_status(), Priority::Low, None, None);
+        task.created_at = now - Duration::days(days_ago);
+        for &(column, days_ago) in moves {
+            let at = now - Duration::days(days_ago);
+            let to = config.columns[column].status();
+            task.transitions.push(Transition {
+                from: task.status.clone(),
+                to: to.clone(),
+                at,
+            });
+            task.completed_at = config.is_done(&to).then_some(at);
+            task.status = to;
+        }
+        task
+    }
+
+    #[test]
+    fn cycle_time_starts_when_work_enters_an_in_progress_column() {
+        let config = BoardConfig::default();
+        let now = Utc::now();
+        let tasks = [task_through(&config, now, 10, &[(1, 8), (2, 5), (3, 2)])];
+
+        let stats = compute(&config, &tasks, None, 4, now);
+        assert_eq!(stats.lead_time.unwrap().median, Duration::days(8));
+        assert_eq!(stats.cycle_time.unwrap().median, Duration::days(3));
+        assert_eq!(stats.throughput.len(), 4);
+        assert_eq!(stats.throughput.iter().map(|(_, n)| n).sum::<usize>(), 1);
+    }
+
+    #[test]
+    fn a_task_that_never_was_in_progress_has_no_cycle_time() {
+        let config = BoardConfig::default();
+        let now = Utc::now();
+        let tasks = [task_through(&config, now, 10, &[(1, 8), (3, 2)])];
+
+        let stats = compute(&config, &tasks, None, 4, now);
+        assert!(stats.lead_time.is_some());
+        assert!(stats.cycle_time.is_none());
+    }
+
+    #[test]
+    fn ageing_only_lists_in_progress_columns() {
+        let mut config = BoardConfig::default();
+        let now = Utc::now();
+        let tasks = [
+            task_through(&config, now, 10, &[(1, 9)]),
+            task_through(&config, now, 10, &[(1, 9), (2, 4)]),
+        ];
+
+        let stats = compute(&config, &tasks, None, 4, now);
+        let ageing: Vec<(&str, Duration)> = stats
+            .ageing
+            .iter()
+            .map(|a| (a.column.as_str(), a.age))
+            .collect();
+        assert_eq!(ageing, [("Doing", Duration::days(4))]);
+
+        config.start_column = Some(config.columns[1].id.clone());
+        let stats = compute(&config, &tasks, None, 4, now);
+        assert_eq!(stats.ageing.len(), 2);
+        assert_eq!(stats.ageing[0].column, "To Do");
+    }
+
+    #[test]
+    fn compute_only_counts_the_given_context() {
+        let config = BoardConfig::default();
+        let now = Utc::now();
+        let mut work = task_through(&config, now, 3, &[(2, 1)]);
+        work.context = "Work".to_string();
+        let home = task_through(&config, now, 3, &[(2, 1)]);
+
+        let stats = compute(&config, &[work, home], Some("work"), 4, now);
+        assert_eq!(stats.wip[2].count, 1);
+        assert_eq!(stats.ageing.len(), 1);
+    }
+
+    #[test]
+    fn flow_counts_a_task_finished_just_before_midnight_on_that_day() {
+        let config = BoardConfig::default();
+        let today = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+        let yesterday = today - Duration::days(1);
+        let done = config.columns.last().unwrap().status();
+
+        let mut task = Task::new("a", config.first_status(), Priority::Low, None, None);
+        task.created_at = local(yesterday - Duration::days(2), 9, 0);
+        let finished = local(yesterday, 23, 59);
+        task.transitions.push(Transition {
+            from: config.first_status(),
+            to: done.clone(),
+            at: finished,
+        });
+        task.status = done;
+        task.completed_at = Some(finished);
+
+        let flow = cumulative_flow(&config, &[task], &[], None, 1, local(today, 12, 0));
+        let last = config.columns.len() - 1;
+        let [.., before, (date, counts), _] = flow.days.as_slice() else {
+            panic!("expected at least three days");
+        };
+        assert_eq!(*date, yesterday);
+        assert_eq!((counts[0], counts[last]), (0, 1));
+        assert_eq!((before.1[0], before.1[last]), (1, 0));
+    }
+}
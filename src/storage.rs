@@ -0,0 +1,195 @@
+use directories::ProjectDirs;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// How many previous versions of the board are kept, as `tasks.json.1`
+/// (newest) to `tasks.json.N` (oldest). See `side_base` for where.
+pub const BACKUP_COUNT: usize = 5;
+
+/// Saves within this long of the newest backup do not take another one, so the
+/// backups span sessions rather than the last few keystrokes.
+pub const BACKUP_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Advisory lock on the board, held for the duration of a read-modify-write.
+/// Released when dropped.
+pub struct FileLock {
+    _file: File,
+}
+
+/// Blocks until this process holds the exclusive lock for `path`.
+/// The lock lives in a separate `<file>.lock` so the board itself can still be replaced atomically.
+pub fn lock(path: &Path) -> io::Result<FileLock> {
+    ensure_parent_dir(path)?;
+    let lock_path = lock_path(path);
+    ensure_parent_dir(&lock_path)?;
+    let file = File::options()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(lock_path)?;
+    file.lock()?;
+    Ok(FileLock { _file: file })
+}
+
+pub struct Backup {
+    pub index: usize,
+    pub path: PathBuf,
+    pub modified: Option<SystemTime>,
+}
+
+/// Writes `content` to a temporary file in the same directory, syncs it and
+/// renames it over `path`, so readers only ever see the old or the new file.
+/// The temporary file has to sit next to `path` for the rename to be atomic;
+/// it only exists for the moment of the write.
+pub fn write_atomic(path: &Path, content: &[u8]) -> io::Result<()> {
+    ensure_parent_dir(path)?;
+    let tmp = sibling(path, &format!(".tmp-{}", std::process::id()), true);
+
+    let result = (|| {
+        let mut file = File::create(&tmp)?;
+        file.write_all(content)?;
+        file.sync_all()?;
+        fs::rename(&tmp, path)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+        return result;
+    }
+
+    sync_parent_dir(path);
+    Ok(())
+}
+
+/// Rotates the backups unless the newest one was taken less than `BACKUP_INTERVAL` ago.
+pub fn backup_if_due(path: &Path) -> io::Result<()> {
+    let newest = fs::metadata(backup_path(path, 1)).and_then(|m| m.modified());
+    let recent = newest
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age < BACKUP_INTERVAL);
+    if recent {
+        return Ok(());
+    }
+    rotate_backups(path)
+}
+
+/// Shifts existing backups up by one and copies the current file to `.1`.
+/// The oldest backup falls off the end.
+pub fn rotate_backups(path: &Path) -> io::Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+    ensure_parent_dir(&backup_path(path, 1))?;
+    for index in (1..BACKUP_COUNT).rev() {
+        let from = backup_path(path, index);
+        if from.exists() {
+            fs::rename(&from, backup_path(path, index + 1))?;
+        }
+    }
+    fs::copy(path, backup_path(path, 1))?;
+    Ok(())
+}
+
+/// Undo history of the board, see `history::History`.
+pub fn journal_path(path: &Path) -> PathBuf {
+    sibling(&side_base(path), ".journal", false)
+}
+
+pub fn lock_path(path: &Path) -> PathBuf {
+    sibling(&side_base(path), ".lock", false)
+}
+
+pub fn backup_path(path: &Path, index: usize) -> PathBuf {
+    sibling(&side_base(path), &format!(".{}", index), false)
+}
+
+/// The path a board's backups, lock and journal are named after. Boards in
+/// myeon's own directories keep them alongside. Any other board, like a
+/// project's `.myeon.json`, keeps them in the `side-files` folder of the myeon
+/// data directory, under its full path with separators turned into `%`, so
+/// nothing but the board itself ends up in the project.
+pub fn side_base(path: &Path) -> PathBuf {
+    // Tests keep side files next to their temporary boards, out of the real data directory.
+    if cfg!(test) {
+        return path.to_path_buf();
+    }
+    let Some(dirs) = ProjectDirs::from("com", "ilseon", "myeon") else {
+        return path.to_path_buf();
+    };
+    let absolute = absolute_path(path);
+    if absolute.starts_with(dirs.config_dir()) || absolute.starts_with(dirs.data_dir()) {
+        return path.to_path_buf();
+    }
+    let name: String = absolute
+        .to_string_lossy()
+        .chars()
+        .map(|c| {
+            if matches!(c, '/' | '\\' | ':') {
+                '%'
+            } else {
+                c
+            }
+        })
+        .collect();
+    dirs.data_dir().join("side-files").join(name)
+}
+
+/// `path` made absolute, with symlinks in its directory resolved when it exists,
+/// so every way of naming the same board agrees.
+fn absolute_path(path: &Path) -> PathBuf {
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    match (absolute.parent(), absolute.file_name()) {
+        (Some(dir), Some(name)) => fs::canonicalize(dir)
+            .map(|dir| dir.join(name))
+            .unwrap_or(absolute),
+        _ => absolute,
+    }
+}
+
+pub fn list_backups(path: &Path) -> Vec<Backup> {
+    (1..=BACKUP_COUNT)
+        .map(|index| backup_path(path, index))
+        .enumerate()
+        .filter(|(_, p)| p.exists())
+        .map(|(i, p)| Backup {
+            index: i + 1,
+            modified: fs::metadata(&p).and_then(|m| m.modified()).ok(),
+            path: p,
+        })
+        .collect()
+}
+
+fn ensure_parent_dir(path: &Path) -> io::Result<()> {
+    match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => fs::create_dir_all(dir),
+        _ => Ok(()),
+    }
+}
+
+/// Builds a path next to `path` with `suffix` appended to its file name,
+/// optionally hidden with a leading dot.
+fn sibling(path: &Path, suffix: &str, hidden: bool) -> PathBuf {
+    let mut name = std::ffi::OsString::new();
+    if hidden {
+        name.push(".");
+    }
+    name.push(path.file_name().unwrap_or_default());
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+#[cfg(unix)]
+fn sync_parent_dir(path: &Path) {
+    // Makes the rename itself durable; failure here is not worth surfacing.
+    if let Some(dir) = path.parent()
+        && let Ok(dir) = File::open(dir)
+    {
+        let _ = dir.sync_all();
+    }
+}
+
+#[cfg(not(unix))]
+fn sync_parent_dir(_path: &Path) {}
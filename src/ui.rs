@@ -1,12 +1,17 @@
-use crate::app::{App, EditField, InputMode};
-use crate::data::{Priority, Task, TaskStatus};
+use crate::app::{self, App, EditField, InputMode};
+use crate::data::{Priority, Task};
+use crate::stats;
 use ratatui::style::Modifier;
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Style},
+    symbols,
     text::{Line, Span},
-    widgets::{Block, BorderType, Borders, List, ListItem, Paragraph},
+    widgets::{
+        Axis, BarChart, Block, BorderType, Borders, Chart, Dataset, GraphType, LegendPosition,
+        List, ListItem, ListState, Paragraph,
+    },
 };
 
 pub const BG_DEEP: Color = Color::Rgb(54, 52, 58);
@@ -16,7 +21,7 @@ pub const BORDER_ACTIVE: Color = Color::Rgb(90, 155, 128);
 pub const BORDER_QUIET: Color = Color::Rgb(31, 31, 31);
 pub const ACCENT_URGENT: Color = Color::Rgb(179, 95, 95);
 
-pub fn render(f: &mut Frame, app: &App) {
+pub fn render(f: &mut Frame, app: &mut App) {
     let main_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints(if matches!(app.input_mode, InputMode::Editing) {
@@ -37,23 +42,143 @@ pub fn render(f: &mut Frame, app: &App) {
         .split(f.area());
 
     render_header(f, app, main_chunks[0]);
-    render_columns(f, app, main_chunks[1]);
+    if matches!(app.input_mode, InputMode::Stats) {
+        render_stats(f, app, main_chunks[1]);
+    } else if matches!(app.input_mode, InputMode::Flow) {
+        render_flow(f, app, main_chunks[1]);
+    } else if matches!(app.input_mode, InputMode::Matrix) {
+        render_matrix(f, app, main_chunks[1]);
+    } else {
+        render_columns(f, app, main_chunks[1]);
+    }
 
     if matches!(app.input_mode, InputMode::Editing) {
         render_input_area(f, app, main_chunks[2]);
     }
+
+    if app.recovery.is_some() {
+        render_recovery_popup(f, app, main_chunks[1]);
+    }
+
+    if matches!(app.input_mode, InputMode::BoardPicker) {
+        render_board_picker(f, app, main_chunks[1]);
+    }
+
+    if matches!(app.input_mode, InputMode::ViewPicker) {
+        render_view_picker(f, app, main_chunks[1]);
+    }
+
+    if let Some(task) = app.detail_task()
+        && matches!(app.input_mode, InputMode::Detail)
+    {
+        render_detail(f, app, task, main_chunks[1]);
+    }
+
+    if matches!(app.input_mode, InputMode::Archive) {
+        render_archive(f, app, main_chunks[1]);
+    }
+
+    if app.showing_trash() {
+        render_trash(f, app, main_chunks[1]);
+    }
+
+    if let Some(confirm) = &app.confirm {
+        render_confirm_popup(f, &confirm.prompt, main_chunks[1]);
+    }
 }
 
 fn render_header(f: &mut Frame, app: &App, area: Rect) {
     let header_text = match app.input_mode {
-        InputMode::Normal => format!(" myeon | Context: [{}]", app.current_context.to_uppercase()),
+        InputMode::Normal => {
+            let mut text = format!(
+                " myeon | Board: {} | Context: [{}]",
+                app.board_name,
+                app.current_context.to_uppercase()
+            );
+            if let Some((name, _)) = &app.active_view {
+                text.push_str(&format!(" | View: {}", name));
+            }
+            if !app.search_query.is_empty() {
+                text.push_str(&format!(
+                    " | Search: \"{}\" (n/N, Esc to clear)",
+                    app.search_query
+                ));
+            }
+            if let Some(message) = &app.status_message {
+                text.push_str(&format!(" | {}", message));
+            }
+            text
+        }
+        InputMode::Search => format!(
+            " myeon | Board: {} | Context: [{}] | Search: {}█ (Enter to keep, Esc to clear)",
+            app.board_name,
+            app.current_context.to_uppercase(),
+            app.search_query
+        ),
+        InputMode::Confirm => " Confirm (y to continue, any other key to cancel) ".to_string(),
+        InputMode::Detail => " Task details (Esc to close) ".to_string(),
+        InputMode::Matrix => match &app.status_message {
+            Some(message) => format!(
+                " myeon | Eisenhower | Context: [{}] | {}",
+                app.current_context.to_uppercase(),
+                message
+            ),
+            None => format!(
+                " myeon | Eisenhower | Context: [{}] (hjkl move card, ↑↓/Tab select, Esc to close)",
+                app.current_context.to_uppercase()
+            ),
+        },
+        InputMode::Stats => format!(
+            " myeon | Stats | Context: [{}] (c to change, f for flow, Esc to close)",
+            app.current_context.to_uppercase()
+        ),
+        InputMode::Flow => format!(
+            " myeon | Cumulative flow, last {} weeks | Context: [{}] (c to change, s for stats, Esc to close)",
+            stats::DEFAULT_WEEKS,
+            app.current_context.to_uppercase()
+        ),
+        InputMode::Archive if app.archive_searching => {
+            " Archive search (type to filter, Enter to finish) ".to_string()
+        }
+        InputMode::Archive => match &app.status_message {
+            Some(message) => format!(
+                " Archive (/ to search, r to put back on the board, Esc to close) | {}",
+                message
+            ),
+            None => " Archive (/ to search, r to put back on the board, Esc to close) ".to_string(),
+        },
+        InputMode::Trash => match &app.status_message {
+            Some(message) => format!(
+                " Trash (r to restore, x to delete for good, Esc to close) | {}",
+                message
+            ),
+            None => " Trash (r to restore, x to delete for good, Esc to close) ".to_string(),
+        },
+        InputMode::BoardPicker => {
+            " Switch board (j/k to choose, Enter to open, Esc to cancel) ".to_string()
+        }
+        InputMode::ViewPicker => {
+            " Filter by a saved view (j/k to choose, Enter to apply, Esc to cancel) ".to_string()
+        }
         InputMode::Editing => " Adding Task (Tab to switch fields, Enter to submit) ".to_string(),
+        InputMode::Recovery => {
+            " myeon | Board could not be loaded, nothing will be saved ".to_string()
+        }
     };
 
-    let header_style = if matches!(app.input_mode, InputMode::Editing) {
-        Style::default().fg(BORDER_ACTIVE)
-    } else {
-        Style::default().fg(FG_MUTED)
+    let header_style = match app.input_mode {
+        InputMode::Editing | InputMode::Search => Style::default().fg(BORDER_ACTIVE),
+        InputMode::Recovery => Style::default().fg(ACCENT_URGENT),
+        InputMode::Normal
+        | InputMode::BoardPicker
+        | InputMode::ViewPicker
+        | InputMode::Confirm
+        | InputMode::Trash
+        | InputMode::Archive
+        | InputMode::Detail
+        | InputMode::Stats
+        | InputMode::Flow
+        | InputMode::Matrix => Style::default().fg(FG_MUTED),
     };
 
     let header = Paragraph::new(header_text).style(header_style).block(
@@ -64,79 +189,66 @@ fn render_header(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(header, area);
 }
 
-fn render_columns(f: &mut Frame, app: &App, area: Rect) {
-    let columns = Layout::default()
+/// What one column needs in order to be drawn.
+struct ColumnView<'a> {
+    title: String,
+    items: Vec<&'a Task>,
+    is_active: bool,
+    selected_index: usize,
+    override_color: Option<Color>,
+    is_dimmed: bool,
+    /// Thresholds for the age cue on cards; empty for none.
+    ageing_days: &'a [u32],
+}
+
+fn render_columns(f: &mut Frame, app: &mut App, area: Rect) {
+    let count = app.config.columns.len() as u32;
+    let areas = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage(25),
-            Constraint::Percentage(25),
-            Constraint::Percentage(25),
-            Constraint::Percentage(25),
-        ])
+        .constraints((0..count).map(|_| Constraint::Ratio(1, count)))
         .split(area);
 
-    let doing_tasks = app.tasks_by_status(TaskStatus::Doing);
-    let doing_border_color = if doing_tasks.len() > 3 {
-        ACCENT_URGENT
-    } else if app.column_index == 2 {
-        BORDER_ACTIVE
-    } else {
-        BORDER_QUIET
-    };
+    let mut scroll = app.column_scroll.clone();
+    scroll.resize(app.config.columns.len(), 0);
 
-    render_column(
-        f,
-        columns[0],
-        "Ideas",
-        &app.tasks_by_status(TaskStatus::Idea),
-        app.column_index == 0,
-        app.selected_task_index,
-        None,
-        app.column_index != 0, // is_dimmed
-    );
-    render_column(
-        f,
-        columns[1],
-        "To Do",
-        &app.tasks_by_status(TaskStatus::Todo),
-        app.column_index == 1,
-        app.selected_task_index,
-        None,
-        app.column_index != 1, // is_dimmed
-    );
-    render_column(
-        f,
-        columns[2],
-        "Doing",
-        &doing_tasks,
-        app.column_index == 2,
-        app.selected_task_index,
-        Some(doing_border_color),
-        app.column_index != 2, // is_dimmed
-    );
-    render_column(
-        f,
-        columns[3],
-        "Done",
-        &app.tasks_by_status(TaskStatus::Done),
-        app.column_index == 3,
-        app.selected_task_index,
-        None,
-        app.column_index != 3, // is_dimmed
-    );
+    for (i, column) in app.config.columns.iter().enumerate() {
+        let mut title = match column.wip_limit {
+            Some(limit) => format!("{} {}/{}", column.name, app.column_load(i), limit),
+            None => column.name.clone(),
+        };
+        if !column.sort.is_manual() {
+            title = format!("{} · {}", title, column.sort.name());
+        }
+        // Gentle soft-cap cue: only the border changes when a column goes over its limit.
+        let over_limit = column
+            .wip_limit
+            .is_some_and(|limit| app.column_load(i) > limit);
+
+        let view = ColumnView {
+            title,
+            items: app.tasks_by_status(&column.status()),
+            is_active: app.column_index == i,
+            selected_index: app.selected_task_index,
+            override_color: over_limit.then_some(ACCENT_URGENT),
+            is_dimmed: app.column_index != i,
+            // Finished work has nowhere left to go, so it does not age.
+            ageing_days: if i + 1 < app.config.columns.len() {
+                &app.config.ageing_days
+            } else {
+                &[]
+            },
+        };
+        render_column(f, areas[i], &view, &mut scroll[i]);
+    }
+
+    app.column_scroll = scroll;
 }
 
-fn render_column(
-    f: &mut Frame,
-    area: Rect,
-    title: &str,
-    items: &[&Task],
-    is_active: bool,
-    selected_index: usize,
-    override_color: Option<Color>,
-    is_dimmed: bool,
-) {
-    let border_color = override_color.unwrap_or(BORDER_ACTIVE);
+/// Draws a column of cards starting at card `*scroll`, moving that offset
+/// as needed to keep the selected card in view.
+fn render_column(f: &mut Frame, area: Rect, view: &ColumnView, scroll: &mut usize) {
+    let is_dimmed = view.is_dimmed;
+    let border_color = view.override_color.unwrap_or(BORDER_ACTIVE);
 
     let border_style = if is_dimmed {
         Style::default()
@@ -159,31 +271,80 @@ fn render_column(
 
     let column_block = Block::default()
         .borders(Borders::ALL)
-        .title(format!(" {} ", title))
+        .title(format!(" {} ", view.title))
         .border_type(BorderType::Thick)
         .border_style(border_style);
 
     let inner_area = column_block.inner(area);
     f.render_widget(column_block, area);
 
-    let mut y_offset = 0u16;
+    let content_width = inner_area.width.saturating_sub(4) as usize;
+    let cards: Vec<(String, String)> = view
+        .items
+        .iter()
+        .map(|task| {
+            let wrapped_title = wrap_text(&task.title, content_width.saturating_sub(1));
+            let description = task.description.clone().unwrap_or_default();
+            (wrapped_title, wrap_text(&description, content_width))
+        })
+        .collect();
+    let heights: Vec<u16> = cards
+        .iter()
+        .map(|(title, desc)| {
+            // Calculate height: borders (2) + title lines + description lines
+            let title_lines = title.lines().count().max(1) as u16;
+            let desc_lines = if desc.is_empty() {
+                0
+            } else {
+                desc.lines().count() as u16
+            };
+            2 + title_lines + desc_lines
+        })
+        .collect();
 
-    for (i, task) in items.iter().enumerate() {
-        let content_width = inner_area.width.saturating_sub(4) as usize;
-        let wrapped_title = wrap_text(&task.title, content_width.saturating_sub(1));
-        let description = task.description.clone().unwrap_or_default();
-        let wrapped_desc = wrap_text(&description, content_width);
+    let selected = view.is_active.then_some(view.selected_index);
+    *scroll = follow_selection(&heights, *scroll, selected, inner_area.height);
+    let visible = visible_count(&heights, *scroll, inner_area.height);
+    let hidden_above = *scroll;
+    let hidden_below = heights.len().saturating_sub(*scroll + visible.max(1));
 
-        // Calculate height: borders (2) + title lines + description lines
-        let title_lines = wrapped_title.lines().count().max(1) as u16;
-        let desc_lines = if wrapped_desc.is_empty() {
-            0
-        } else {
-            wrapped_desc.lines().count() as u16
-        };
-        let card_height = 2 + title_lines + desc_lines; // 2 for top/bottom border
+    let hint_style = Style::default().fg(fg_muted);
+    let mut y_offset = 0u16;
+    if hidden_above > 0 {
+        render_overflow_hint(
+            f,
+            inner_area,
+            0,
+            &format!("↑ {} more", hidden_above),
+            hint_style,
+        );
+        y_offset += 1;
+    }
+    let bottom = if hidden_below > 0 {
+        let y = inner_area.height.saturating_sub(1);
+        render_overflow_hint(
+            f,
+            inner_area,
+            y,
+            &format!("+{} more", hidden_below),
+            hint_style,
+        );
+        y
+    } else {
+        inner_area.height
+    };
 
-        if y_offset + card_height > inner_area.height {
+    // A card taller than the whole column is still shown, cut off at the bottom.
+    for (i, task) in view
+        .items
+        .iter()
+        .enumerate()
+        .skip(*scroll)
+        .take(visible.max(1))
+    {
+        let (wrapped_title, wrapped_desc) = &cards[i];
+        let card_height = heights[i].min(bottom.saturating_sub(y_offset));
+        if card_height == 0 {
             break;
         }
 
@@ -193,7 +354,7 @@ fn render_column(
             width: inner_area.width,
             height: card_height,
         };
-        let is_selected = is_active && i == selected_index;
+        let is_selected = view.is_active && i == view.selected_index;
 
         let (indicator, indicator_color) = match task.priority {
             Priority::High => (
@@ -250,19 +411,102 @@ fn render_column(
             }
         }
 
-        let card = Paragraph::new(lines).block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(card_border_color))
-                .style(Style::default().bg(BG_DEEP)),
-        );
+        let mut card_block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(card_border_color))
+            .style(Style::default().bg(BG_DEEP));
+        // The short number you type on the command line, tucked into the border.
+        if task.number > 0 {
+            card_block = card_block.title(
+                Line::from(Span::styled(
+                    format!(" #{} ", task.number),
+                    Style::default().fg(fg_muted),
+                ))
+                .right_aligned(),
+            );
+        }
+        if let Some(cue) = age_cue(task, view.ageing_days) {
+            card_block = card_block.title_bottom(
+                Line::from(Span::styled(cue, Style::default().fg(fg_muted))).right_aligned(),
+            );
+        }
+        let card = Paragraph::new(lines).block(card_block);
 
         f.render_widget(card, card_area);
         y_offset += card_height + 1; // +1 for spacing between cards
     }
 }
 
+/// A quiet "·· 9d" once a task has sat in its column past the first threshold,
+/// with one dot per threshold passed. Deliberately the same muted tone as the task number.
+fn age_cue(task: &Task, thresholds: &[u32]) -> Option<String> {
+    let days = (chrono::Utc::now() - task.entered_column_at()).num_days();
+    let passed = thresholds.iter().filter(|&&t| days >= t.into()).count();
+    (passed > 0).then(|| format!(" {} {}d ", "·".repeat(passed), days))
+}
+
+fn render_overflow_hint(f: &mut Frame, inner_area: Rect, y: u16, text: &str, style: Style) {
+    if y >= inner_area.height {
+        return;
+    }
+    let area = Rect {
+        x: inner_area.x,
+        y: inner_area.y + y,
+        width: inner_area.width,
+        height: 1,
+    };
+    f.render_widget(
+        Paragraph::new(text.to_string())
+            .style(style)
+            .alignment(ratatui::layout::Alignment::Center),
+        area,
+    );
+}
+
+/// How many cards fit from `offset` in `height` lines, leaving a line
+/// for each overflow hint that will be shown.
+fn visible_count(heights: &[u16], offset: usize, height: u16) -> usize {
+    let fit = |available: u16| {
+        let mut used = 0u16;
+        let mut count = 0;
+        for h in heights.iter().skip(offset) {
+            let needed = if count == 0 { *h } else { h + 1 };
+            if used + needed > available {
+                break;
+            }
+            used += needed;
+            count += 1;
+        }
+        count
+    };
+
+    let available = height.saturating_sub(u16::from(offset > 0));
+    let count = fit(available);
+    if offset + count < heights.len() {
+        fit(available.saturating_sub(1))
+    } else {
+        count
+    }
+}
+
+/// Adjusts a column's scroll offset so the selected card (if any) is visible,
+/// and scrolls back up when cards were removed and there is room again.
+fn follow_selection(heights: &[u16], offset: usize, selected: Option<usize>, height: u16) -> usize {
+    let len = heights.len();
+    let mut offset = offset.min(len.saturating_sub(1));
+    if let Some(selected) = selected.filter(|s| *s < len) {
+        offset = offset.min(selected);
+        while offset < selected && offset + visible_count(heights, offset, height) <= selected {
+            offset += 1;
+        }
+    }
+    while offset > 0 && offset - 1 + visible_count(heights, offset - 1, height) >= len {
+        offset -= 1;
+    }
+    offset
+}
+
 fn wrap_text(text: &str, max_width: usize) -> String {
     if max_width == 0 || text.is_empty() {
         return text.to_string();
@@ -296,9 +540,10 @@ fn render_input_area(f: &mut Frame, app: &App, area: Rect) {
         .direction(Direction::Horizontal)
         .constraints([
             Constraint::Percentage(30),
-            Constraint::Percentage(30),
-            Constraint::Percentage(20),
-            Constraint::Percentage(20),
+            Constraint::Percentage(28),
+            Constraint::Percentage(18),
+            Constraint::Percentage(12),
+            Constraint::Percentage(12),
         ])
         .split(area);
 
@@ -336,6 +581,13 @@ fn render_input_area(f: &mut Frame, app: &App, area: Rect) {
         matches!(app.active_edit_field, EditField::Priority),
         chunks[3],
     );
+    render_input_field(
+        f,
+        " Urgency ",
+        &format!("{:?}", app.editing_urgency),
+        matches!(app.active_edit_field, EditField::Urgency),
+        chunks[4],
+    );
 
     if matches!(app.active_edit_field, EditField::Context) {
         render_context_popup(f, app, chunks[2]);
@@ -394,3 +646,666 @@ fn render_context_popup(f: &mut Frame, app: &App, anchor: Rect) {
     f.render_widget(ratatui::widgets::Clear, popup_area);
     f.render_widget(list, popup_area);
 }
+
+fn render_recovery_popup(f: &mut Frame, app: &App, anchor: Rect) {
+    let Some(recovery) = &app.recovery else {
+        return;
+    };
+
+    let width = anchor.width.saturating_sub(8).min(72);
+    let text_width = width.saturating_sub(4) as usize;
+    let mut lines = vec![Line::from(Span::styled(
+        "The board file could not be read.",
+        Style::default().fg(FG_PRIMARY),
+    ))];
+    lines.push(Line::from(""));
+    for line in wrap_text(&recovery.reason, text_width).lines() {
+        lines.push(Line::from(Span::styled(
+            line.to_string(),
+            Style::default().fg(FG_MUTED),
+        )));
+    }
+    lines.push(Line::from(""));
+    let recover_hint = if recovery.quarantined.is_some() {
+        " r  retry the moved file once you have fixed it"
+    } else {
+        " r  retry loading the board"
+    };
+    let mut hints = vec![recover_hint];
+    if recovery.can_start_fresh {
+        hints.push(" n  start fresh (the old file is kept)");
+    }
+    hints.push(" q  quit without saving");
+    for hint in hints {
+        lines.push(Line::from(Span::styled(
+            hint,
+            Style::default().fg(FG_PRIMARY),
+        )));
+    }
+
+    let height = (lines.len() as u16 + 2).min(anchor.height);
+    let popup_area = Rect {
+        x: anchor.x + anchor.width.saturating_sub(width) / 2,
+        y: anchor.y + anchor.height.saturating_sub(height) / 2,
+        width,
+        height,
+    };
+
+    let popup = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(" Recovery ")
+            .border_style(Style::default().fg(ACCENT_URGENT))
+            .style(Style::default().bg(BG_DEEP)),
+    );
+    f.render_widget(ratatui::widgets::Clear, popup_area);
+    f.render_widget(popup, popup_area);
+}
+
+fn render_board_picker(f: &mut Frame, app: &App, anchor: Rect) {
+    let width = anchor.width.saturating_sub(8).min(48);
+    let height = (app.board_list.len() as u16 + 2).min(anchor.height);
+    let popup_area = Rect {
+        x: anchor.x + anchor.width.saturating_sub(width) / 2,
+        y: anchor.y + anchor.height.saturating_sub(height) / 2,
+        width,
+        height,
+    };
+
+    let items: Vec<ListItem> = app
+        .board_list
+        .iter()
+        .enumerate()
+        .map(|(i, board)| {
+            let style = if i == app.board_list_index {
+                Style::default().fg(Color::Black).bg(BORDER_ACTIVE)
+            } else if board.path == app.board_path {
+                Style::default().fg(FG_PRIMARY)
+            } else {
+                Style::default().fg(FG_MUTED)
+            };
+            ListItem::new(format!(" {}", board.name)).style(style)
+        })
+        .collect();
+
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(" Boards ")
+            .border_style(Style::default().fg(BORDER_ACTIVE))
+            .style(Style::default().bg(BG_DEEP)),
+    );
+    f.render_widget(ratatui::widgets::Clear, popup_area);
+    f.render_widget(list, popup_area);
+}
+
+fn render_view_picker(f: &mut Frame, app: &App, anchor: Rect) {
+    let width = anchor.width.saturating_sub(8).min(64);
+    let height = (app.config.views.len() as u16 + 3).min(anchor.height);
+    let popup_area = Rect {
+        x: anchor.x + anchor.width.saturating_sub(width) / 2,
+        y: anchor.y + anchor.height.saturating_sub(height) / 2,
+        width,
+        height,
+    };
+
+    let active = app.active_view.as_ref().map(|(name, _)| name.as_str());
+    let rows = std::iter::once(("All tasks", "")).chain(
+        app.config
+            .views
+            .iter()
+            .map(|v| (v.name.as_str(), v.query.as_str())),
+    );
+    let items: Vec<ListItem> = rows
+        .enumerate()
+        .map(|(i, (name, query))| {
+            let style = if i == app.view_index {
+                Style::default().fg(Color::Black).bg(BORDER_ACTIVE)
+            } else if active == Some(name) || (i == 0 && active.is_none()) {
+                Style::default().fg(FG_PRIMARY)
+            } else {
+                Style::default().fg(FG_MUTED)
+            };
+            ListItem::new(format!(" {:<16} {}", name, query)).style(style)
+        })
+        .collect();
+
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(" Views ")
+            .border_style(Style::default().fg(BORDER_ACTIVE))
+            .style(Style::default().bg(BG_DEEP)),
+    );
+    f.render_widget(ratatui::widgets::Clear, popup_area);
+    f.render_widget(list, popup_area);
+}
+
+fn render_stats(f: &mut Frame, app: &App, area: Rect) {
+    let stats = stats::compute(
+        &app.config,
+        &app.all_tasks,
+        app.context_filter(),
+        stats::DEFAULT_WEEKS,
+        chrono::Utc::now(),
+    );
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+    let top = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .split(rows[0]);
+    let bottom = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .split(rows[1]);
+    let panel = |title: &str| {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(format!(" {} ", title))
+            .border_style(Style::default().fg(BORDER_QUIET))
+    };
+    let muted = |text: &str| {
+        Line::from(Span::styled(
+            text.to_string(),
+            Style::default().fg(FG_MUTED),
+        ))
+    };
+
+    let mut times = Vec::new();
+    for (label, summary) in [
+        ("Lead time", &stats.lead_time),
+        ("Cycle time", &stats.cycle_time),
+    ] {
+        times.push(Line::from(Span::styled(
+            format!(" {}", label),
+            Style::default().fg(FG_PRIMARY).add_modifier(Modifier::BOLD),
+        )));
+        match summary {
+            Some(s) => {
+                times.push(muted(&format!(
+                    "   average {}, median {}",
+                    stats::format_duration(s.average),
+                    stats::format_duration(s.median)
+                )));
+                times.push(muted(&format!("   over {} finished tasks", s.count)));
+            }
+            None => times.push(muted("   no finished tasks yet")),
+        }
+        times.push(Line::from(""));
+    }
+    f.render_widget(Paragraph::new(times).block(panel("Time to finish")), top[0]);
+
+    let labels: Vec<String> = stats
+        .throughput
+        .iter()
+        .map(|(week, _)| week.format("%m-%d").to_string())
+        .collect();
+    let data: Vec<(&str, u64)> = labels
+        .iter()
+        .zip(&stats.throughput)
+        .map(|(label, (_, count))| (label.as_str(), *count as u64))
+        .collect();
+    let chart = BarChart::default()
+        .block(panel("Finished per week"))
+        .data(data.as_slice())
+        .bar_width(5)
+        .bar_gap(2)
+        .bar_style(Style::default().fg(BORDER_ACTIVE))
+        .value_style(Style::default().fg(Color::Black).bg(BORDER_ACTIVE))
+        .label_style(Style::default().fg(FG_MUTED));
+    f.render_widget(chart, top[1]);
+
+    let wip: Vec<Line> = stats
+        .wip
+        .iter()
+        .map(|column| {
+            let count = match column.limit {
+                Some(limit) => format!("{}/{}", column.count, limit),
+                None => column.count.to_string(),
+            };
+            let over = column.limit.is_some_and(|limit| column.count > limit);
+            Line::from(vec![
+                Span::styled(
+                    format!(" {:<16}", column.name),
+                    Style::default().fg(FG_MUTED),
+                ),
+                Span::styled(
+                    count,
+                    Style::default().fg(if over { ACCENT_URGENT } else { FG_PRIMARY }),
+                ),
+            ])
+        })
+        .collect();
+    f.render_widget(
+        Paragraph::new(wip).block(panel("Work in progress")),
+        bottom[0],
+    );
+
+    let mut ageing: Vec<Line> = stats
+        .ageing
+        .iter()
+        .map(|item| {
+            Line::from(vec![
+                Span::styled(
+                    format!(
+                        " {:>6}  {:<10} ",
+                        stats::format_duration(item.age),
+                        item.column
+                    ),
+                    Style::default().fg(FG_MUTED),
+                ),
+                Span::styled(item.title.clone(), Style::default().fg(FG_PRIMARY)),
+            ])
+        })
+        .collect();
+    if ageing.is_empty() {
+        ageing.push(muted(" Nothing in progress"));
+    }
+    f.render_widget(
+        Paragraph::new(ageing).block(panel("Ageing in progress")),
+        bottom[1],
+    );
+}
+
+/// The Eisenhower matrix: important on top, urgent on the left.
+fn render_matrix(f: &mut Frame, app: &App, area: Rect) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)])
+        .split(area);
+    let subtitles = [
+        "important, urgent",
+        "important, not urgent",
+        "urgent, not important",
+        "neither",
+    ];
+
+    for (row, row_area) in rows.iter().enumerate() {
+        let cells = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)])
+            .split(*row_area);
+        for (column, cell) in cells.iter().enumerate() {
+            let quadrant = row * 2 + column;
+            let is_active = quadrant == app.matrix_quadrant;
+            let tasks = app.matrix_tasks(quadrant);
+            let selected = app.matrix_index.min(tasks.len().saturating_sub(1));
+            let items: Vec<ListItem> = tasks
+                .iter()
+                .enumerate()
+                .map(|(i, task)| {
+                    let style = if is_active && i == selected {
+                        Style::default().fg(Color::Black).bg(BORDER_ACTIVE)
+                    } else {
+                        Style::default().fg(FG_PRIMARY)
+                    };
+                    ListItem::new(Line::from(vec![
+                        Span::styled(format!(" {} ", task.title), style),
+                        Span::styled(
+                            format!("#{} {}", task.number, app.column_name(&task.status)),
+                            Style::default().fg(FG_MUTED),
+                        ),
+                    ]))
+                })
+                .collect();
+            let border = if is_active {
+                BORDER_ACTIVE
+            } else {
+                BORDER_QUIET
+            };
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .border_type(if is_active {
+                    BorderType::Thick
+                } else {
+                    BorderType::Rounded
+                })
+                .title(format!(
+                    " {} · {} ({}) ",
+                    app::MATRIX_QUADRANTS[quadrant],
+                    subtitles[quadrant],
+                    tasks.len()
+                ))
+                .border_style(Style::default().fg(border));
+            let mut state = ListState::default().with_selected(is_active.then_some(selected));
+            f.render_stateful_widget(List::new(items).block(block), *cell, &mut state);
+        }
+    }
+}
+
+/// Muted colours for the bands of the flow diagram, first column first.
+const FLOW_COLOURS: [Color; 5] = [
+    Color::Rgb(120, 120, 128),
+    Color::Rgb(150, 140, 110),
+    Color::Rgb(90, 155, 128),
+    Color::Rgb(100, 130, 160),
+    Color::Rgb(140, 110, 140),
+];
+
+/// Draws the cumulative flow as stacked lines: the last column at the bottom,
+/// each line above adding the next column back, so the gaps between lines are
+/// the columns' loads and a widening band is a bottleneck.
+fn render_flow(f: &mut Frame, app: &App, area: Rect) {
+    let flow = stats::cumulative_flow(
+        &app.config,
+        &app.all_tasks,
+        &app.trash,
+        app.context_filter(),
+        stats::DEFAULT_WEEKS,
+        chrono::Utc::now(),
+    );
+
+    let lines: Vec<Vec<(f64, f64)>> = (0..flow.columns.len())
+        .map(|column| {
+            flow.days
+                .iter()
+                .enumerate()
+                .map(|(day, (_, counts))| {
+                    (day as f64, counts[column..].iter().sum::<usize>() as f64)
+                })
+                .collect()
+        })
+        .collect();
+    let datasets: Vec<Dataset> = flow
+        .columns
+        .iter()
+        .zip(&lines)
+        .enumerate()
+        .map(|(i, (name, points))| {
+            Dataset::default()
+                .name(name.clone())
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(FLOW_COLOURS[i % FLOW_COLOURS.len()]))
+                .data(points)
+        })
+        .collect();
+
+    let last_day = flow.days.len().saturating_sub(1);
+    let highest = lines
+        .first()
+        .and_then(|top| top.iter().map(|(_, y)| *y).reduce(f64::max))
+        .unwrap_or(0.0)
+        .max(1.0);
+    let date_label = |index: usize| {
+        flow.days
+            .get(index)
+            .map(|(date, _)| date.format("%m-%d").to_string())
+            .unwrap_or_default()
+    };
+    let x_labels = vec![
+        date_label(0),
+        date_label(last_day / 2),
+        date_label(last_day),
+    ];
+    let y_labels = vec![
+        "0".to_string(),
+        format!("{}", (highest / 2.0).round()),
+        format!("{}", highest),
+    ];
+
+    let chart = Chart::new(datasets)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(BORDER_QUIET)),
+        )
+        .x_axis(
+            Axis::default()
+                .style(Style::default().fg(FG_MUTED))
+                .bounds([0.0, last_day as f64])
+                .labels(x_labels),
+        )
+        .y_axis(
+            Axis::default()
+                .style(Style::default().fg(FG_MUTED))
+                .bounds([0.0, highest])
+                .labels(y_labels),
+        )
+        .legend_position(Some(LegendPosition::TopLeft))
+        .hidden_legend_constraints((Constraint::Ratio(1, 3), Constraint::Ratio(1, 2)));
+    f.render_widget(chart, area);
+}
+
+fn render_detail(f: &mut Frame, app: &App, task: &Task, anchor: Rect) {
+    let width = anchor.width.saturating_sub(8).min(72);
+    let local = |t: chrono::DateTime<chrono::Utc>| {
+        t.with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string()
+    };
+    let field = |label: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!(" {:<10}", label), Style::default().fg(FG_MUTED)),
+            Span::styled(value, Style::default().fg(FG_PRIMARY)),
+        ])
+    };
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!(" {}", task.title),
+            Style::default().fg(FG_PRIMARY).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        field("Column", app.column_name(&task.status).to_string()),
+        field("Priority", format!("{:?}", task.priority)),
+        field("Urgency", format!("{:?}", task.urgency)),
+        field("Context", task.context.clone()),
+        field("Created", local(task.created_at)),
+    ];
+    if let Some(updated) = task.updated_at {
+        lines.push(field("Updated", local(updated)));
+    }
+    if let Some(completed) = task.completed_at {
+        lines.push(field("Completed", local(completed)));
+    }
+    if let Some(description) = task.description.as_ref().filter(|d| !d.is_empty()) {
+        lines.push(Line::from(""));
+        for line in wrap_text(description, width.saturating_sub(4) as usize).lines() {
+            lines.push(Line::from(Span::styled(
+                format!(" {}", line),
+                Style::default().fg(FG_PRIMARY),
+            )));
+        }
+    }
+
+    lines.push(Line::from(""));
+    if task.transitions.is_empty() {
+        lines.push(Line::from(Span::styled(
+            " Not moved yet",
+            Style::default().fg(FG_MUTED),
+        )));
+    }
+    for transition in &task.transitions {
+        lines.push(field(
+            "Moved",
+            format!(
+                "{}  {} → {}",
+                local(transition.at),
+                app.column_name(&transition.from),
+                app.column_name(&transition.to)
+            ),
+        ));
+    }
+
+    let height = (lines.len() as u16 + 2).min(anchor.height);
+    let popup_area = Rect {
+        x: anchor.x + anchor.width.saturating_sub(width) / 2,
+        y: anchor.y + anchor.height.saturating_sub(height) / 2,
+        width,
+        height,
+    };
+    let detail = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(format!(" #{} ", task.number))
+            .border_style(Style::default().fg(BORDER_ACTIVE))
+            .style(Style::default().bg(BG_DEEP)),
+    );
+    f.render_widget(ratatui::widgets::Clear, popup_area);
+    f.render_widget(detail, popup_area);
+}
+
+fn render_archive(f: &mut Frame, app: &App, anchor: Rect) {
+    let tasks = app.archived_tasks();
+    let width = anchor.width.saturating_sub(8).min(72);
+    let height = (tasks.len().max(1) as u16 + 2).min(anchor.height.saturating_sub(2));
+    let popup_area = Rect {
+        x: anchor.x + anchor.width.saturating_sub(width) / 2,
+        y: anchor.y + anchor.height.saturating_sub(height) / 2,
+        width,
+        height,
+    };
+
+    let title = if app.archive_searching || !app.archive_query.is_empty() {
+        let cursor = if app.archive_searching { "_" } else { "" };
+        format!(" Archive /{}{} ", app.archive_query, cursor)
+    } else {
+        format!(" Archive ({}) ", tasks.len())
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title(title)
+        .border_style(Style::default().fg(BORDER_ACTIVE))
+        .style(Style::default().bg(BG_DEEP));
+    f.render_widget(ratatui::widgets::Clear, popup_area);
+
+    if tasks.is_empty() {
+        let text = if app.archive_query.is_empty() {
+            " Nothing archived yet"
+        } else {
+            " No archived task matches"
+        };
+        let empty = Paragraph::new(text)
+            .style(Style::default().fg(FG_MUTED))
+            .block(block);
+        f.render_widget(empty, popup_area);
+        return;
+    }
+
+    let items: Vec<ListItem> = tasks
+        .iter()
+        .enumerate()
+        .map(|(i, task)| {
+            let style = if i == app.archive_index {
+                Style::default().fg(Color::Black).bg(BORDER_ACTIVE)
+            } else {
+                Style::default().fg(FG_PRIMARY)
+            };
+            let archived = task
+                .archived_at
+                .map(|t| {
+                    t.with_timezone(&chrono::Local)
+                        .format("%Y-%m-%d")
+                        .to_string()
+                })
+                .unwrap_or_default();
+            ListItem::new(format!(
+                " #{:<4} {}  {}  [{}]",
+                task.number, archived, task.title, task.context
+            ))
+            .style(style)
+        })
+        .collect();
+
+    let mut state = ListState::default().with_selected(Some(app.archive_index));
+    f.render_stateful_widget(List::new(items).block(block), popup_area, &mut state);
+}
+
+fn render_trash(f: &mut Frame, app: &App, anchor: Rect) {
+    let width = anchor.width.saturating_sub(8).min(64);
+    let height = (app.trash.len().max(1) as u16 + 2).min(anchor.height);
+    let popup_area = Rect {
+        x: anchor.x + anchor.width.saturating_sub(width) / 2,
+        y: anchor.y + anchor.height.saturating_sub(height) / 2,
+        width,
+        height,
+    };
+
+    let title = match app.config.trash_days {
+        0 => " Trash ".to_string(),
+        days => format!(" Trash (kept for {} days) ", days),
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title(title)
+        .border_style(Style::default().fg(BORDER_ACTIVE))
+        .style(Style::default().bg(BG_DEEP));
+    f.render_widget(ratatui::widgets::Clear, popup_area);
+
+    if app.trash.is_empty() {
+        let empty = Paragraph::new(" The trash is empty")
+            .style(Style::default().fg(FG_MUTED))
+            .block(block);
+        f.render_widget(empty, popup_area);
+        return;
+    }
+
+    let items: Vec<ListItem> = app
+        .trash
+        .iter()
+        .enumerate()
+        .map(|(i, trashed)| {
+            let style = if i == app.trash_index {
+                Style::default().fg(Color::Black).bg(BORDER_ACTIVE)
+            } else {
+                Style::default().fg(FG_PRIMARY)
+            };
+            let deleted = trashed
+                .deleted_at
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d");
+            ListItem::new(format!(" {}  {}", deleted, trashed.task.title)).style(style)
+        })
+        .collect();
+
+    let mut state = ListState::default().with_selected(Some(app.trash_index));
+    f.render_stateful_widget(List::new(items).block(block), popup_area, &mut state);
+}
+
+fn render_confirm_popup(f: &mut Frame, prompt: &str, anchor: Rect) {
+    let width = anchor.width.saturating_sub(8).min(56);
+    let mut lines: Vec<Line> = wrap_text(prompt, width.saturating_sub(4) as usize)
+        .lines()
+        .map(|line| {
+            Line::from(Span::styled(
+                format!(" {}", line),
+                Style::default().fg(FG_PRIMARY),
+            ))
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        " y  yes    any other key  no",
+        Style::default().fg(FG_MUTED),
+    )));
+
+    let height = (lines.len() as u16 + 2).min(anchor.height);
+    let popup_area = Rect {
+        x: anchor.x + anchor.width.saturating_sub(width) / 2,
+        y: anchor.y + anchor.height.saturating_sub(height) / 2,
+        width,
+        height,
+    };
+
+    let popup = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(BORDER_ACTIVE))
+            .style(Style::default().bg(BG_DEEP)),
+    );
+    f.render_widget(ratatui::widgets::Clear, popup_area);
+    f.render_widget(popup, popup_area);
+}